@@ -0,0 +1,25 @@
+//! Interface of the DEX routers the treasury swaps through.
+//!
+//! The governor approves the router for exactly the input amount before each
+//! swap and checks what it actually received afterwards, so a router only
+//! has to honour the minimum output to be safe to register.
+
+use ink::primitives::AccountId;
+
+#[ink::trait_definition]
+pub trait Router {
+    /// Swaps exactly `amount_in` of `token_in`, taken from the caller with
+    /// `transfer_from`, for at least `min_amount_out` of `token_out` sent to
+    /// `to`. Returns the amount sent.
+    #[ink(message)]
+    fn swap_exact_in(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+        min_amount_out: u128,
+        to: AccountId,
+    ) -> u128;
+}
+
+pub const SWAP_EXACT_IN: [u8; 4] = ink::selector_bytes!("Router::swap_exact_in");
@@ -0,0 +1,15 @@
+//! Interface of the governors this DAO can vote in with its own holdings.
+//!
+//! A meta-governance proposal makes the governor cast a vote as the voter in
+//! another protocol's governance, weighted by whatever that protocol counts
+//! for the DAO's account.
+
+#[ink::trait_definition]
+pub trait Governor {
+    /// Casts the caller's vote on `proposal_id`, with `support` 0 for against,
+    /// 1 for and 2 for abstain. Returns whether the vote was counted.
+    #[ink(message)]
+    fn cast_vote(&mut self, proposal_id: u32, support: u8) -> bool;
+}
+
+pub const CAST_VOTE: [u8; 4] = ink::selector_bytes!("Governor::cast_vote");
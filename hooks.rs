@@ -0,0 +1,38 @@
+//! Interface of the optional extension contract the governor calls on every
+//! proposal lifecycle operation.
+//!
+//! Each hook runs with the gas limit configured in the governor. Returning
+//! `false`, trapping or running out of gas rejects the operation, which lets an
+//! extension enforce custom rules (KYC, rate limits) or simply record
+//! notifications without changes to the governor itself.
+
+use ink::primitives::AccountId;
+
+#[ink::trait_definition]
+pub trait GovernorHooks {
+    #[ink(message)]
+    fn on_propose(&mut self, proposal_id: u32, proposer: AccountId) -> bool;
+
+    #[ink(message)]
+    fn on_vote(&mut self, proposal_id: u32, voter: AccountId, weight: u128) -> bool;
+
+    #[ink(message)]
+    fn on_execute(&mut self, proposal_id: u32, executor: AccountId) -> bool;
+
+    #[ink(message)]
+    fn on_cancel(&mut self, proposal_id: u32, canceler: AccountId) -> bool;
+}
+
+/// Membership gate consulted before an account may propose or vote, e.g. a
+/// KYC registry for legally wrapped DAOs.
+#[ink::trait_definition]
+pub trait EligibilityChecker {
+    #[ink(message)]
+    fn is_eligible(&self, account: AccountId) -> bool;
+}
+
+pub const ON_PROPOSE: [u8; 4] = ink::selector_bytes!("GovernorHooks::on_propose");
+pub const ON_VOTE: [u8; 4] = ink::selector_bytes!("GovernorHooks::on_vote");
+pub const ON_EXECUTE: [u8; 4] = ink::selector_bytes!("GovernorHooks::on_execute");
+pub const ON_CANCEL: [u8; 4] = ink::selector_bytes!("GovernorHooks::on_cancel");
+pub const IS_ELIGIBLE: [u8; 4] = ink::selector_bytes!("EligibilityChecker::is_eligible");
@@ -1,197 +1,8318 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
 
-#[ink::contract]
+mod dex;
+mod governor;
+mod hooks;
+mod merkle;
+mod migration;
+mod oracle;
+mod pair;
+mod rule;
+mod signature;
+mod strategy;
+mod tally;
+mod token;
+mod weight;
+
+/// Every message has an explicit selector, equal to the one ink derives from
+/// its name, so renaming or reordering messages never changes the ABI. These
+/// selectors are stable: a message may be removed but its selector is never
+/// reassigned to another message.
+#[openbrush::contract]
 mod voting_contract {
+    use crate::dex;
+    use crate::governor;
+    use crate::hooks;
+    use crate::merkle;
+    use crate::migration;
+    use crate::oracle;
+    use crate::pair;
+    use crate::rule::{TallyRule, Votes};
+    use crate::signature::{self, Operation};
+    use crate::strategy;
+    use crate::tally::{self, ProposalState, Runoff};
+    use crate::token::{self, TokenRef};
+    use crate::weight::{self, Checkpoint};
 
-    use openbrush::contracts::psp22::PSP22Ref;
+    use openbrush::contracts::access_control::*;
+    use openbrush::contracts::psp22::extensions::burnable::PSP22BurnableRef;
+    use openbrush::contracts::psp22::{PSP22Receiver, PSP22ReceiverError, PSP22Ref};
+    use openbrush::traits::Storage;
 
+    use ink::env::call::{build_call, build_create, ExecutionInput, Selector};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::collections::BTreeMap;
+    use ink::prelude::format;
     use ink::prelude::string::String;
     use ink::prelude::string::ToString;
     use ink::prelude::vec::Vec;
-    use ink::storage::Mapping;
+    use ink::storage::{Lazy, Mapping};
+    use scale::Encode;
 
     pub const ONE_MINUTE: u64 = 60 * 1000;
+    pub const ONE_DAY: u64 = 24 * 60 * ONE_MINUTE;
+    pub const MAX_CATEGORY_LENGTH: usize = 16;
+    pub const MAX_BASIS_POINTS: u16 = 10_000;
+    /// Upper bound for any delay a parameter change may set.
+    pub const MAX_DELAY: u64 = 30 * ONE_DAY;
+    /// Hard ceilings for the configurable payload limits, keeping execution
+    /// well within a block's weight.
+    pub const MAX_ACTIONS_LIMIT: u32 = 64;
+    pub const MAX_PAYLOAD_SIZE_LIMIT: u32 = 16 * 1024;
+    pub const MAX_COSIGNERS: u32 = 16;
+    pub const MAX_SPONSORS: u32 = 16;
+    /// Approval, in basis points of for and against votes, a failed proposal
+    /// has to exceed for its sponsors to go without a strike.
+    pub const SPONSOR_STRIKE_THRESHOLD: u16 = 1_000;
+    /// Strikes after which an account can no longer sponsor proposals, until a
+    /// `ClearSponsorStrikes` action clears them.
+    pub const MAX_SPONSOR_STRIKES: u32 = 3;
+    /// Time between executing a `RotateGuardian` proposal and the incoming
+    /// guardian taking over, during which guardians can call the rotation off.
+    pub const GUARDIAN_HANDOVER_DELAY: u64 = 2 * ONE_DAY;
+    pub const MAX_TRANCHES: u32 = 12;
+    pub const MAX_CLAIM_PERIOD: u64 = 365 * ONE_DAY;
+    pub const MAX_HOOK_GAS_LIMIT: u64 = 50_000_000_000;
+    pub const MAX_LP_POOLS: u32 = 8;
+    pub const MAX_STRATEGIES: u32 = 4;
+    pub const MAX_ACTIVE_PROPOSALS_LIMIT: u32 = 64;
+    pub const MAX_CIPHERTEXT_SIZE: u32 = 512;
+    /// Time after an encrypted vote ends for the committee to reveal its key.
+    /// Once it has passed without a key, the proposal can be finalized as
+    /// failed.
+    pub const BALLOT_REVEAL_PERIOD: u64 = 7 * ONE_DAY;
+    pub const MAX_OPTIONS: u32 = 8;
+    pub const MAX_DISTINCT_RANKINGS: u32 = 128;
+    pub const MAX_CANDIDATES: u32 = 32;
+    pub const MAX_TRANSLATIONS: u32 = 16;
+    pub const MAX_LOCALE_LENGTH: usize = 16;
+    pub const MAX_GRANT_REPORTS: u32 = 16;
+    /// Bytes of a call's return value kept in the execution log.
+    pub const MAX_CALL_OUTPUT: usize = 256;
+    /// Share of for and against votes, in basis points, a super-majority has to
+    /// exceed: the minimum approval threshold of a proposal that shuts the
+    /// governor down, and the one `TallyRule::SuperMajority` applies.
+    pub const SUPER_MAJORITY_THRESHOLD: u16 = 6_667;
+    pub const MAX_STRATEGY_MULTIPLIER: u32 = 100_000;
+    /// Number of delegates kept in the leaderboard.
+    pub const TOP_DELEGATES_CAPACITY: u32 = 32;
+    /// Largest page any paginated view returns.
+    pub const MAX_PAGE_SIZE: u32 = 50;
+    /// Number of recent state transitions kept in the on-chain changelog.
+    pub const CHANGELOG_CAPACITY: u64 = 256;
+    /// Age after which checkpoints are pruned. No proposal's vote can last
+    /// longer, so no snapshot still being voted on is older.
+    pub const CHECKPOINT_RETENTION: u64 = MAX_DELAY;
+    /// Wait after the first failed execution before another failure is
+    /// recorded. It doubles with every recorded failure, up to the maximum.
+    pub const RETRY_BASE_DELAY: u64 = 10 * ONE_MINUTE;
+    pub const MAX_RETRY_DELAY: u64 = ONE_DAY;
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    /// Admin of every other role. Held only by the contract itself, so roles can
+    /// only be granted or revoked by executed proposals.
+    pub const ADMIN_ROLE: RoleType = DEFAULT_ADMIN_ROLE;
+    pub const PROPOSER_ROLE: RoleType = ink::selector_id!("PROPOSER_ROLE");
+    pub const EXECUTOR_ROLE: RoleType = ink::selector_id!("EXECUTOR_ROLE");
+    pub const GUARDIAN_ROLE: RoleType = ink::selector_id!("GUARDIAN_ROLE");
+    /// Holds the key that decrypts ballots of proposals with encrypted voting.
+    pub const COMMITTEE_ROLE: RoleType = ink::selector_id!("COMMITTEE_ROLE");
+    /// Held by the members of the elected council.
+    pub const COUNCIL_ROLE: RoleType = ink::selector_id!("COUNCIL_ROLE");
+    pub const COUNCIL_CATEGORY: &str = "COUNCIL";
+    pub const EMERGENCY_CATEGORY: &str = "EMERGENCY";
+    pub const DISPUTE_CATEGORY: &str = "DISPUTE";
+    pub const WATCHDOG_CATEGORY: &str = "WATCHDOG";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
     pub enum VoteType {
         Against,
         For,
+        Abstain,
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
     pub enum GovernorError {
         AmountShouldNotBeZero,
         DurationError,
         ProposalNotFound,
         ProposalAlreadyExecuted,
-        VotePeriodEnded,
-        VotePeriodNotEnded,
+        VotePeriodEnded {
+            ended_at: Timestamp,
+            now: Timestamp,
+        },
+        /// `ends_at` is zero while the proposal waits in the queue or as a draft.
+        VotePeriodNotEnded {
+            ends_at: Timestamp,
+            now: Timestamp,
+        },
         TransferError,
         ProposalNotAccepted,
+        InsufficientTreasuryBalance {
+            available: Balance,
+            required: Balance,
+        },
+        InvalidCategory,
+        MissingRole,
+        TimelockNotElapsed {
+            executable_at: Timestamp,
+            now: Timestamp,
+        },
+        ProposalAlreadyFinalized,
+        /// `counted` is the weight the proposal's tally rule counts towards the
+        /// quorum.
+        QuorumNotReached {
+            counted: Balance,
+            quorum: Balance,
+        },
+        InvalidParameter,
+        AlreadyVoted,
+        NoVotingWeight,
+        UnsupportedWeightSource,
+        InsufficientLockedBalance,
+        TokensLocked,
+        XcmUnavailable,
+        XcmDispatchFailed,
+        ReferendaUnavailable,
+        ReferendumSubmissionFailed,
+        ContractVoterNotAllowed,
+        SignatureExpired,
+        InvalidNonce,
+        InvalidSignature,
+        StrategyNotRegistered,
+        CodeHashMismatch,
+        DependencyCodeChanged,
+        NotDelegating,
+        NotCosigner,
+        AlreadyCosigned,
+        CosignaturesMissing,
+        EscrowNotFound,
+        NotRecipient,
+        ClaimPeriodNotOver,
+        TokenTransferFailed,
+        FeePaymentFailed,
+        IncorrectBond,
+        ProposalCanceled,
+        HookRejected,
+        NotEligible,
+        TokenDeploymentFailed,
+        AirdropNotFound,
+        AlreadyClaimed,
+        InvalidProof,
+        AirdropExhausted,
+        ProposalNotSettled,
+        NothingToReclaim,
+        GovernorShutDown,
+        ShutdownPending,
+        MigrationRejected,
+        ProposalPending,
+        DuplicateProposal,
+        BallotsEncrypted,
+        BallotsNotEncrypted,
+        BallotKeyNotRevealed,
+        BallotKeyAlreadyRevealed,
+        TallyIncomplete,
+        RankedBallotRequired,
+        NotRankedChoice,
+        InvalidRanking,
+        TooManyRankings,
+        NotElection,
+        ApprovalBallotRequired,
+        InvalidApprovals,
+        NominationClosed,
+        NominationOpen,
+        AlreadyCandidate,
+        TooManyCandidates,
+        NotCandidate,
+        EmergencyDisabled,
+        EmergencyCapExceeded,
+        EmergencyPending,
+        PriceUnavailable,
+        PriceStale,
+        ProposalExpired,
+        NotProposer,
+        TranslationExists,
+        TooManyTranslations,
+        ExternalVoteFailed,
+        DexUnavailable,
+        SwapFailed,
+        StakingUnavailable,
+        StakingFailed,
+        TreasuryStaked,
+        NotDraft,
+        DiscussionPeriodNotOver,
+        VoteWeightBelowMinimum {
+            have: Balance,
+            need: Balance,
+        },
+        GrantNotFound,
+        GrantClosed,
+        TooManyReports,
+        NotArbiter,
+        EscrowNotAttested,
+        EscrowDisputed,
+        PayloadHidden,
+        PayloadMismatch,
+        AlreadySponsored,
+        SponsorsMissing,
+        ReminderNotDue,
+        AlreadyReminded,
+        HandoverNotFound,
+        HandoverDelayNotOver,
+        GuardianPowersExpired,
+        TokenMetadataUnavailable,
+        DisbursementNotApproved,
+        ObjectionWindowOpen,
+        DisbursementObjected,
+        NotWatchdog,
+        AlreadyWatchdog,
+        ProposalPaused,
+        AlreadyPaused,
+        WatchdogsDisabled,
+        ProposalNotPassed,
+        ReviewPending,
+        MilestonePlanNotFound,
+        NoTrancheRemaining,
+        PayloadTooLarge,
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// Where voting weight comes from. The primary source is fixed at
+    /// deployment; proposals can add further `Strategy`s on top of it.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(
         feature = "std",
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
-    pub struct Proposal {
-        for_address: AccountId,
-        against_address: AccountId,
-        to: AccountId,
-        title: String,
-        description: String,
-        amount: Balance,
-        vote_start: Timestamp,
-        vote_end: Timestamp,
-        executed: bool,
+    pub enum WeightSource {
+        /// Balance of the governance PSP22 token, as delegated, plus the tokens
+        /// backing registered LP positions.
+        Psp22,
+        /// Native balance locked in the governor through `lock`.
+        Native,
+        /// Governance tokens locked in the governor through `lock_tokens`. Unlike
+        /// delegated balances, locked tokens can't be moved to another account
+        /// and vote again until every vote they counted in has ended.
+        LockedPsp22,
+        /// Amount the voter has staked in the runtime, read through the chain
+        /// extension function `func_id`.
+        RuntimeStaking { func_id: u32 },
+        /// Weight reported by a registered strategy adapter contract, see the
+        /// `strategy` module. Counts as zero once the adapter is deregistered.
+        Adapter(AccountId),
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// An additional weight source counted on top of the primary one, scaled by
+    /// `multiplier` in basis points (`10_000` counts it one to one).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(
         feature = "std",
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
-    pub struct ProposalVote {
-        against_votes: u8,
-        for_votes: u8,
+    pub struct Strategy {
+        source: WeightSource,
+        multiplier: u32,
     }
 
-    pub type ProposalId = u32;
-
-    pub type Result<T> = core::result::Result<T, GovernorError>;
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct GovernorConfig {
+        /// PSP22 token used for `WeightSource::Psp22` weight and proposal fees.
+        pub governance_token: AccountId,
+        pub weight_source: WeightSource,
+        /// Decimals of the native currency, used for `WholeTokenAmounts` and to
+        /// convert `UsdPayout` amounts at the price feed's rate.
+        pub native_decimals: u8,
+        /// Reject proposals the treasury could not pay out at creation time.
+        pub validate_treasury_on_propose: bool,
+        /// Minimum combined for, against and abstain weight for a proposal to pass.
+        pub quorum: Balance,
+        /// Share of the for and against weight, in basis points, that `for` must
+        /// exceed. Abstentions only count towards quorum.
+        pub approval_threshold: u16,
+        /// Longest voting period a proposal may request, in minutes.
+        pub max_vote_duration: u64,
+        /// Delay after `vote_end` before a passed proposal can be executed.
+        pub timelock: u64,
+        /// Time after the timelock during which only `EXECUTOR_ROLE` may execute;
+        /// afterwards anyone can.
+        pub executor_grace_period: u64,
+        /// Maximum number of actions a single proposal may carry.
+        pub max_actions: u32,
+        /// Maximum SCALE-encoded size of all actions of a proposal, in bytes.
+        pub max_payload_size: u32,
+        /// Chain extension function dispatching XCM messages, if the runtime
+        /// provides one. Required for `ProposalAction::XcmSend`.
+        pub xcm_func_id: Option<u32>,
+        /// Chain extension function submitting referenda to the runtime's
+        /// governance pallet, if the runtime provides one. Required for
+        /// `ProposalAction::SubmitReferendum`.
+        pub referenda_func_id: Option<u32>,
+        /// Payouts above this amount also need `required_cosignatures` approvals
+        /// from the cosigner set after the vote passes.
+        pub cosign_threshold: Option<Balance>,
+        pub required_cosignatures: u32,
+        /// Time recipients have to claim an escrowed payout before anyone may
+        /// return it to the treasury. Zero means payouts never expire.
+        pub claim_period: u64,
+        /// Governance tokens burned from the proposer on every `propose`, or kept
+        /// in the treasury if the token isn't burnable. The governor must be
+        /// approved to spend them.
+        pub proposal_fee: Balance,
+        /// Native deposit sent with `propose`, reclaimable through `reclaim_bond`
+        /// if the proposal passes and slashed otherwise.
+        pub proposal_bond: Balance,
+        /// Share of a slashed bond, in basis points, paid to whoever finalizes the
+        /// proposal. The rest stays in the treasury.
+        pub slash_bounty: u16,
+        /// Extension contract implementing `hooks::GovernorHooks`, if any.
+        pub extension: Option<AccountId>,
+        /// Gas each call into a hook, eligibility checker or pair adapter may use.
+        pub hook_gas_limit: u64,
+        /// Contract implementing `hooks::EligibilityChecker`, if voting and
+        /// proposing are gated.
+        pub eligibility_checker: Option<AccountId>,
+        /// Native deposit per byte of proposal text and actions, sent with
+        /// `propose` on top of the bond and refunded once the proposal is pruned.
+        pub storage_deposit_per_byte: Balance,
+        /// Proposals that can be open for voting at once. Further proposals wait
+        /// in a first-in, first-out queue and open as earlier votes end.
+        pub max_active_proposals: u32,
+        /// Time after a proposal's defeat during which an identical proposal is
+        /// rejected.
+        pub resubmission_cooldown: u64,
+        /// Chain extension function decrypting ballots. While set, new proposals
+        /// take encrypted ballots only, which are tallied after the committee
+        /// reveals the proposal's key once voting has ended.
+        pub ballot_decryption_func_id: Option<u32>,
+        /// Native deposit sent with `register_candidate`, refunded after the
+        /// election unless the candidate received no approvals.
+        pub candidacy_bond: Balance,
+        /// Largest payout a guardian or council member can make through
+        /// `emergency_spend`. Zero disables emergency spending.
+        pub emergency_cap: Balance,
+        /// Native deposit sent with `emergency_spend`, slashed like a proposal
+        /// bond if the spend is not ratified.
+        pub emergency_bond: Balance,
+        /// Bond an account posts with `register_watchdog`.
+        pub watchdog_bond: Balance,
+        /// Feed converting `UsdPayout` amounts into the native token, if any.
+        pub price_feed: Option<PriceFeed>,
+        /// Oldest price, in milliseconds, a `UsdPayout` may be converted with.
+        pub max_price_age: u64,
+        /// Time after the timelock during which a passed proposal can be
+        /// executed. Zero means proposals never expire.
+        pub execution_window: u64,
+        /// Contract implementing `dex::Router` that `Swap` actions trade through.
+        pub dex_router: Option<AccountId>,
+        /// Chain extension function managing the governor's nomination pool
+        /// membership, if the runtime provides one. Required for staking actions.
+        pub pool_staking_func_id: Option<u32>,
+        /// Time new proposals spend as drafts before their proposer can open
+        /// them for voting with `activate`. Zero opens proposals right away.
+        pub discussion_period: u64,
+        /// Largest share, in basis points, of `proposal_bond` waived for a
+        /// proposer whose proposals all got executed. Scales with the share of
+        /// executed proposals. Zero disables discounts.
+        pub max_bond_discount: u16,
+        /// Executed proposals a proposer needs before getting any discount.
+        pub reputation_min_executed: u32,
+        /// Delegations not renewed with `delegate` for this long lose half their
+        /// weight, and again for every further period, once `decay_delegation`
+        /// is called. Zero disables decay.
+        pub delegation_decay_period: u64,
+        /// Smallest total weight a ballot may carry. Lighter ballots are
+        /// rejected, so dust balances can't fill storage with receipts.
+        pub min_vote_weight: Balance,
+        /// Time checkpointed weight must have been held before a snapshot to
+        /// count, so balances bought or borrowed right before a proposal opens
+        /// add nothing. Zero counts everything recorded before the snapshot.
+        pub min_holding_period: u64,
+        /// Sponsors a draft needs before `activate` can open it. Zero disables
+        /// sponsorship, otherwise every new proposal starts as a draft.
+        pub required_sponsors: u32,
+        /// Weight a sponsor must have held before sponsoring.
+        pub min_sponsor_weight: Balance,
+        /// Payouts above this amount are paid in two phases: anyone can
+        /// `approve_disbursement`, and once `objection_window` has passed
+        /// without a guardian objecting, anyone can `disburse`. `None` pays all
+        /// payouts through `claim` alone.
+        pub disbursement_threshold: Option<Balance>,
+        /// Shorter than a nonzero `claim_period`.
+        pub objection_window: u64,
+        /// Final stretch of a vote in which `poke` emits `VotingEndingSoon`.
+        /// Zero disables reminders.
+        pub reminder_window: u64,
+        /// Time at which guardian and council powers lapse unless token holders
+        /// extend it with a `GuardianPowersUntil` change. `None` never lapses.
+        pub guardian_powers_until: Option<Timestamp>,
+        /// Whether contract accounts may vote, guarding live-balance strategies
+        /// against flash-loan wrappers.
+        pub contract_voters: ContractVoters,
+    }
 
-    #[ink(storage)]
-    pub struct VotingContract {
-        proposal_votes: Mapping<ProposalId, ProposalVote>,
-        proposals: Mapping<ProposalId, Proposal>,
-        next_proposal_id: u32,
-        governance_token: AccountId,
+    /// A contract implementing `oracle::PriceOracle` for the native token.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PriceFeed {
+        pub oracle: AccountId,
     }
 
-    impl VotingContract {
-        #[ink(constructor)]
-        pub fn new(token_address: AccountId) -> Self {
+    impl GovernorConfig {
+        /// Reasonable settings for a token-weighted governor; override fields as
+        /// needed before deploying.
+        pub fn default_config(governance_token: AccountId) -> Self {
             Self {
-                proposal_votes: Mapping::new(),
-                proposals: Mapping::new(),
-                next_proposal_id: 0,
-                governance_token: token_address,
+                governance_token,
+                weight_source: WeightSource::Psp22,
+                native_decimals: 12,
+                validate_treasury_on_propose: false,
+                quorum: 0,
+                approval_threshold: 5_000,
+                max_vote_duration: 7 * 24 * 60,
+                timelock: ONE_DAY,
+                executor_grace_period: 2 * ONE_DAY,
+                max_actions: 16,
+                max_payload_size: 4 * 1024,
+                xcm_func_id: None,
+                referenda_func_id: None,
+                cosign_threshold: None,
+                required_cosignatures: 0,
+                claim_period: 30 * ONE_DAY,
+                proposal_fee: 0,
+                proposal_bond: 0,
+                slash_bounty: 1_000,
+                extension: None,
+                hook_gas_limit: 5_000_000_000,
+                eligibility_checker: None,
+                storage_deposit_per_byte: 0,
+                max_active_proposals: MAX_ACTIVE_PROPOSALS_LIMIT,
+                resubmission_cooldown: 7 * ONE_DAY,
+                ballot_decryption_func_id: None,
+                candidacy_bond: 0,
+                emergency_cap: 0,
+                emergency_bond: 0,
+                watchdog_bond: 0,
+                price_feed: None,
+                max_price_age: 60 * ONE_MINUTE,
+                execution_window: 0,
+                dex_router: None,
+                pool_staking_func_id: None,
+                discussion_period: 0,
+                max_bond_discount: 0,
+                reputation_min_executed: 3,
+                delegation_decay_period: 0,
+                min_vote_weight: 0,
+                min_holding_period: 0,
+                required_sponsors: 0,
+                min_sponsor_weight: 0,
+                disbursement_threshold: None,
+                objection_window: ONE_DAY,
+                reminder_window: ONE_DAY,
+                guardian_powers_until: None,
+                contract_voters: ContractVoters::Allowed,
             }
         }
+    }
 
-        #[ink(message)]
-        pub fn propose(
-            &mut self,
-            for_address: AccountId,
-            against_address: AccountId,
+    /// A governance parameter together with the value a proposal sets it to.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ParameterChange {
+        ValidateTreasuryOnPropose(bool),
+        Quorum(Balance),
+        ApprovalThreshold(u16),
+        MaxVoteDuration(u64),
+        Timelock(u64),
+        ExecutorGracePeriod(u64),
+        MaxActions(u32),
+        MaxPayloadSize(u32),
+        CosignThreshold(Option<Balance>),
+        RequiredCosignatures(u32),
+        ClaimPeriod(u64),
+        ProposalFee(Balance),
+        ProposalBond(Balance),
+        SlashBounty(u16),
+        HookGasLimit(u64),
+        StorageDepositPerByte(Balance),
+        MaxActiveProposals(u32),
+        ResubmissionCooldown(u64),
+        BallotDecryptionFuncId(Option<u32>),
+        CandidacyBond(Balance),
+        EmergencyCap(Balance),
+        EmergencyBond(Balance),
+        MaxPriceAge(u64),
+        ExecutionWindow(u64),
+        DiscussionPeriod(u64),
+        MaxBondDiscount(u16),
+        ReputationMinExecuted(u32),
+        DelegationDecayPeriod(u64),
+        MinVoteWeight(Balance),
+        MinHoldingPeriod(u64),
+        RequiredSponsors(u32),
+        MinSponsorWeight(Balance),
+        ReminderWindow(u64),
+        DisbursementThreshold(Option<Balance>),
+        ObjectionWindow(u64),
+        WatchdogBond(Balance),
+        GuardianPowersUntil(Option<Timestamp>),
+        ContractVoters(ContractVoters),
+        PoolStakingFuncId(Option<u32>),
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ProposalAction {
+        GrantRole {
+            role: RoleType,
+            account: AccountId,
+        },
+        RevokeRole {
+            role: RoleType,
+            account: AccountId,
+        },
+        ChangeParameter(ParameterChange),
+        /// Sends an XCM message from the governor's sovereign account. Both fields
+        /// are SCALE-encoded: a `VersionedMultiLocation` and a `VersionedXcm<()>`.
+        XcmSend {
+            dest: Vec<u8>,
+            message: Vec<u8>,
+        },
+        /// Notes `call`, a SCALE-encoded runtime call, as a preimage and submits
+        /// it as a referendum on the runtime governance `track`, recording the
+        /// referendum index against the proposal.
+        SubmitReferendum {
+            track: u16,
+            call: Vec<u8>,
+        },
+        /// Records the index of a runtime referendum submitted outside the
+        /// governor that this proposal mirrors or coordinates with.
+        LinkReferendum(u32),
+        /// Adds or removes a contract code hash from the voters allowed under
+        /// `ContractVoters::AllowListed`.
+        AllowVoterCode {
+            code_hash: Hash,
+            allowed: bool,
+        },
+        /// Replaces the set of accounts that co-sign large payouts.
+        SetCosigners(Vec<AccountId>),
+        /// Approves paying `recipient` in tranches, each released separately by a
+        /// guardian or a `ReleaseTranche` proposal.
+        MilestonePayout {
+            recipient: AccountId,
+            tranches: Vec<Balance>,
+        },
+        ReleaseTranche(ProposalId),
+        /// Cancels the unreleased tranches of a milestone payout.
+        CancelMilestones(ProposalId),
+        /// Returns an unclaimed escrowed payout to the treasury.
+        Clawback(ProposalId),
+        /// Sends the governor's whole balance of a PSP22 token it doesn't manage
+        /// to `to`, e.g. tokens sent to the governor by mistake.
+        SweepToken {
+            token: AccountId,
             to: AccountId,
-            title: String,
-            description: String,
+        },
+        /// Registers, or with `None` removes, the extension contract called on
+        /// every lifecycle operation.
+        SetExtension(Option<AccountId>),
+        /// Registers, or with `None` removes, the contract deciding who may
+        /// propose and vote.
+        SetEligibilityChecker(Option<AccountId>),
+        /// Instantiates a new PSP22 from `code_hash` by calling `constructor` with
+        /// `total_supply`, which the token must mint to the governor. With a
+        /// `migration_root` the new token becomes the governance token and its
+        /// supply is handed out as an airdrop against that root.
+        DeployToken {
+            code_hash: Hash,
+            constructor: [u8; 4],
+            total_supply: Balance,
+            salt: Vec<u8>,
+            migration_root: Option<Hash>,
+        },
+        /// Opens an airdrop or retroactive funding round paying up to `budget` of
+        /// `token`, or of native funds for `None`, to the leaves under `root`.
+        RegisterAirdrop {
+            token: Option<AccountId>,
+            root: Hash,
+            budget: Balance,
+        },
+        /// Ends an airdrop. Unclaimed native funds return to the treasury.
+        CloseAirdrop(u32),
+        /// Counts the governance-token side of `lp_token` positions as voting
+        /// weight, valued by `adapter`. Registering a token again replaces its
+        /// adapter.
+        RegisterLpToken {
+            lp_token: AccountId,
+            adapter: AccountId,
+        },
+        /// Replaces the strategies aggregated on top of the primary weight
+        /// source. Proposals keep the strategies in force when they were created.
+        SetStrategies(Vec<Strategy>),
+        UnregisterLpToken(AccountId),
+        /// Allows `adapter` in `WeightSource::Adapter` strategies while its code
+        /// hash is `code_hash`. Execution fails if the adapter runs other code.
+        RegisterStrategy {
+            adapter: AccountId,
+            code_hash: Hash,
+        },
+        /// Removes `adapter` from the registry and from the current strategies.
+        DeregisterStrategy(AccountId),
+        /// Pins `contract` to `code_hash`, which must be its current code, or
+        /// unpins it if `None`. Functionality depending on a pinned contract
+        /// stops while the contract runs other code.
+        PinCode {
+            contract: AccountId,
+            code_hash: Option<Hash>,
+        },
+        /// Winds the DAO down. The treasury goes to `successor`, or with a
+        /// `distribution_root` becomes a native airdrop holders claim pro rata
+        /// within `claim_period`. No proposals or votes are accepted afterwards,
+        /// and once all funds held for others are settled anyone can `terminate`
+        /// the governor, sending what is left to `successor`. Proposals carrying
+        /// this action need at least `SUPER_MAJORITY_THRESHOLD` approval.
+        Shutdown {
+            successor: AccountId,
+            distribution_root: Option<Hash>,
+        },
+        /// Hands the DAO over to `new_governor`, which must implement
+        /// `migration::SuccessorGovernor`: it learns the governance token and the
+        /// open proposals and receives the treasury, after which this governor
+        /// is shut down with `new_governor` as successor. Needs the same
+        /// super-majority as `Shutdown`.
+        MigrateTo(AccountId),
+        /// Makes the proposal a ranked-choice vote between these options, decided
+        /// by instant runoff when it is finalized. Executing it has no effect.
+        Options(Vec<String>),
+        /// Makes the proposal an approval-voting election for `seats` seats.
+        /// Candidates register during the first `nomination_period` of the vote,
+        /// after which voters approve any subset of them. The `seats` candidates
+        /// with the most approved weight are elected.
+        Election {
+            seats: u32,
+            nomination_period: u64,
+        },
+        /// Sets, or with `None` stops, the schedule of council elections. A newly
+        /// scheduled council holds its first election right away.
+        SetCouncilSchedule(Option<CouncilSchedule>),
+        /// Pays the proposal's `to` account `bps` basis points of the treasury
+        /// balance at execution, at most `cap`, escrowed like a fixed payout.
+        /// Replaces the proposal's `amount`, which must be zero.
+        TreasuryShare {
+            bps: u16,
+            cap: Balance,
+        },
+        /// Pays the proposal's `to` account the native equivalent of `usd`, in
+        /// the price feed's units, at the feed's price at execution, at most
+        /// `max_amount`. Replaces the proposal's `amount`, which must be zero.
+        UsdPayout {
+            usd: Balance,
+            max_amount: Balance,
+        },
+        /// Sets, or with `None` removes, the price feed used by `UsdPayout`.
+        SetPriceFeed(Option<PriceFeed>),
+        /// Sells exactly `amount_in` of the treasury's `token_in` for at least
+        /// `min_amount_out` of `token_out` through the registered DEX router.
+        Swap {
+            token_in: AccountId,
+            token_out: AccountId,
+            amount_in: Balance,
+            min_amount_out: Balance,
+        },
+        /// Sets, or with `None` removes, the DEX router used by `Swap`.
+        SetDexRouter(Option<AccountId>),
+        /// Bonds idle treasury funds into nomination pool `pool_id`. The governor
+        /// is a member of one pool at a time.
+        Stake {
+            pool_id: u32,
             amount: Balance,
-            duration: u64,
-        ) -> Result<()> {
-            if amount == 0 {
-                return Err(GovernorError::AmountShouldNotBeZero);
-            }
-            if duration == 0 || duration > 60 * ONE_MINUTE {
-                return Err(GovernorError::DurationError);
-            }
+        },
+        /// Starts unbonding staked treasury funds.
+        Unstake(Balance),
+        /// Returns funds whose unbonding period has passed to the treasury.
+        WithdrawUnbonded,
+        /// Casts the governor's own vote on a proposal of another contract
+        /// implementing `governor::Governor`.
+        CastExternalVote {
+            governor: AccountId,
+            proposal_id: u32,
+            vote: VoteType,
+        },
+        /// Registers, or with `None` removes, the voting rules of a category.
+        SetCategoryTemplate {
+            category: String,
+            template: Option<CategoryTemplate>,
+        },
+        /// Holds the proposal's payout in escrow until `arbiter` attests that the
+        /// funded work was completed. If the arbiter reports it wasn't, a
+        /// `ResolveEscrow` proposal is opened in the `DISPUTE` category.
+        Arbitrated(AccountId),
+        /// Settles an arbitrated or objected escrow: `release` lets the payout go
+        /// ahead, otherwise it returns to the treasury.
+        ResolveEscrow {
+            escrow: ProposalId,
+            release: bool,
+        },
+        /// Makes `incoming` a guardian, replacing `outgoing` if set, once
+        /// `GUARDIAN_HANDOVER_DELAY` has passed after execution. Guardians can't
+        /// be added with `GrantRole`.
+        RotateGuardian {
+            incoming: AccountId,
+            outgoing: Option<AccountId>,
+        },
+        /// Marks the proposal's `amount` and the amounts of its `Swap`,
+        /// `RegisterAirdrop`, `Stake`, `Unstake`, `MilestonePayout`,
+        /// `TreasuryShare` and `UsdPayout` actions as whole tokens. `propose`
+        /// converts them to the smallest unit using `native_decimals` or the
+        /// token's `PSP22Metadata` decimals, and drops this marker. Proposals
+        /// with a `DeployToken` action can't use it.
+        WholeTokenAmounts,
+        /// Lifts a watchdog's pause of a proposal as frivolous, slashing the
+        /// watchdog's bond to the treasury. Proposed automatically on every
+        /// pause; if it fails, the pause stands. A canceled review, or one that
+        /// passed but expired unexecuted, lifts the pause the same way.
+        LiftPause(ProposalId),
+        /// Stands in for actions kept secret until voting ends, e.g. swaps that
+        /// could be front-run. Holds the Blake2x256 hash of the SCALE-encoded
+        /// `(Vec<ProposalAction>, [u8; 32])`, the actions and a salt of the
+        /// proposer's choosing that keeps guessable actions from being found
+        /// by hashing candidates. The proposer reveals both with
+        /// `reveal_actions` before the proposal can be executed.
+        Hidden(Hash),
+        /// Closes the grant paid out by an executed proposal as completed or
+        /// failed, after its recipient reported on the work.
+        CloseGrant {
+            grant: ProposalId,
+            completed: bool,
+        },
+        /// Clears an account's sponsor strikes, restoring its right to sponsor.
+        /// Strikes are otherwise permanent.
+        ClearSponsorStrikes(AccountId),
+    }
 
-            let now = self.env().block_timestamp();
-            let proposal = Proposal {
-                for_address,
-                against_address,
-                to,
-                title,
-                description,
-                amount,
-                vote_start: now,
-                vote_end: now + duration * ONE_MINUTE,
-                executed: false,
-            };
+    /// What executing a proposal will do, in a form wallets can render as a
+    /// preview without knowing the governor's internal action encoding.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Action {
+        /// Pays `amount` of the native currency to `to`.
+        NativeTransfer {
+            to: AccountId,
+            amount: Balance,
+        },
+        /// Moves `amount` of a PSP22 token to `to`. For sweeps this is the
+        /// balance held at the time of the query.
+        TokenTransfer {
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        },
+        /// Calls `selector` on `callee` with the SCALE encoded `input`.
+        ContractCall {
+            callee: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+        },
+        ParameterChange(ParameterChange),
+        /// Replaces the governor's code.
+        Upgrade {
+            code_hash: Hash,
+        },
+        /// Any other governance action, e.g. role or cosigner changes.
+        Governance(ProposalAction),
+    }
 
-            let id = self.next_proposal_id();
-            self.proposals.insert(id, &proposal);
+    /// Voting rules that override the global configuration for proposals of one
+    /// category. Unset fields fall back to the global value.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CategoryTemplate {
+        quorum: Option<Balance>,
+        approval_threshold: Option<u16>,
+        tally_rule: Option<TallyRule>,
+    }
 
-            Ok(())
-        }
-        #[ink(message)]
-        pub fn execute(&mut self, proposal_id: ProposalId) -> Result<()> {
-            let mut proposal = self
-                .proposals
-                .get(&proposal_id)
-                .ok_or(GovernorError::ProposalNotFound)?;
+    /// An executed payout waiting for its recipient to `claim` it.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Escrow {
+        recipient: AccountId,
+        amount: Balance,
+        created_at: Timestamp,
+        /// Account that must attest completion before the payout can be claimed.
+        arbiter: Option<AccountId>,
+        attested: bool,
+        /// Resolution proposal opened when the arbiter disputed completion.
+        dispute: Option<ProposalId>,
+        /// Whether the payout was above `disbursement_threshold` when escrowed.
+        two_phase: bool,
+        /// End of the objection window, once the disbursement was approved.
+        disbursable_at: Option<Timestamp>,
+        /// Set by a guardian's objection until a `ResolveEscrow` proposal
+        /// settles the payout.
+        objected: bool,
+    }
 
-            if proposal.executed {
-                return Err(GovernorError::ProposalAlreadyExecuted);
-            }
+    /// Funds claimable by the accounts listed in a merkle tree. Each leaf is
+    /// `(airdrop_id, account, amount)`. A `token` of `None` pays out native
+    /// funds, whose unclaimed budget stays reserved from the treasury.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Airdrop {
+        token: Option<AccountId>,
+        root: Hash,
+        budget: Balance,
+        claimed: Balance,
+    }
 
-            let now = self.env().block_timestamp();
+    /// A DEX LP token whose holders get weight for their governance-token side.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LpPool {
+        lp_token: AccountId,
+        /// Contract implementing `pair::PairAdapter` for this LP token.
+        adapter: AccountId,
+    }
 
-            if now < proposal.vote_end {
-                return Err(GovernorError::VotePeriodNotEnded);
-            }
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct GuardianHandover {
+        incoming: AccountId,
+        outgoing: Option<AccountId>,
+        activates_at: Timestamp,
+    }
 
-            let weight_for = self.account_weight(proposal.for_address);
-            let weight_against = self.account_weight(proposal.against_address);
-            let mut proposal_current_votes =
-                self.proposal_votes.get(proposal_id).unwrap();
-            proposal_current_votes.for_votes = weight_for;
-            proposal_current_votes.against_votes = weight_against;
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct MilestonePlan {
+        recipient: AccountId,
+        tranches: Vec<Balance>,
+        /// Number of tranches paid out so far.
+        released: u32,
+        canceled: bool,
+    }
 
-            if proposal_current_votes.against_votes >= proposal_current_votes.for_votes {
-                return Err(GovernorError::ProposalNotAccepted);
-            }
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ContractVoters {
+        Allowed,
+        Denied,
+        /// Only contracts whose code hash was allowed with `AllowVoterCode`.
+        AllowListed,
+    }
 
-            proposal.executed = true;
-            self.env()
-                .transfer(proposal.to, proposal.amount)
-                .map_err(|_| GovernorError::TransferError)?;
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum GrantStatus {
+        Open,
+        Completed,
+        Failed,
+    }
 
-            self.proposals.insert(&proposal_id, &proposal);
+    /// Deliverable reports posted for the grant an executed proposal paid out.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct GrantRecord {
+        recipient: AccountId,
+        /// Hashes of the reports, oldest first.
+        reports: Vec<Hash>,
+        status: GrantStatus,
+    }
 
-            Ok(())
-        }
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        proposer: AccountId,
+        for_address: AccountId,
+        against_address: AccountId,
+        to: AccountId,
+        category: String,
+        category_number: u32,
+        amount: Balance,
+        vote_start: Timestamp,
+        vote_end: Timestamp,
+        /// Governance parameters in force when the proposal was created. Later
+        /// parameter changes only apply to proposals created afterwards.
+        quorum: Balance,
+        approval_threshold: u16,
+        timelock: u64,
+        executor_grace_period: u64,
+        execution_window: u64,
+        cosign_threshold: Option<Balance>,
+        required_cosignatures: u32,
+        tally_rule: TallyRule,
+    }
 
-        #[ink(message)]
-        pub fn get_proposal_vote(&self, proposal_id: ProposalId) -> Option<ProposalVote> {
-            let proposal = self
-                .proposals
-                .get(&proposal_id)
-                .ok_or(GovernorError::ProposalNotFound)
-                .ok()?;
-            let weight_for = self.account_weight(proposal.for_address);
-            let weight_against = self.account_weight(proposal.against_address);
-            let mut proposal_current_votes =
-                self.proposal_votes.get(proposal_id).unwrap();
-            proposal_current_votes.for_votes = weight_for;
-            proposal_current_votes.against_votes = weight_against;
+    /// Authorization of an operation submitted on the signer's behalf.
+    struct Signed {
+        operation: Operation,
+        nonce: u64,
+        deadline: Timestamp,
+        signature: [u8; 65],
+    }
 
-            Some(proposal_current_votes)
-        }
+    /// Everything `propose` takes, bundled so `propose_by_sig` can have it
+    /// signed as one value.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ProposalRequest {
+        pub for_address: AccountId,
+        pub against_address: AccountId,
+        pub to: AccountId,
+        pub title: String,
+        pub description: String,
+        pub category: String,
+        pub amount: Balance,
+        pub duration: u64,
+        pub actions: Vec<ProposalAction>,
+    }
 
-        #[ink(message)]
-        pub fn get_proposal(&self, proposal_id: u32) -> Option<Proposal> {
-            self.proposals.get(proposal_id)
-        }
+    /// Long-form proposal text, stored apart from `Proposal` so that timing and
+    /// state reads don't pay for loading it.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalText {
+        title: String,
+        description: String,
+    }
 
-        #[ink(message)]
-        pub fn get_proposals_size(&self) -> ProposalId {
-            self.next_proposal_id
-        }
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalVote {
+        against_votes: Balance,
+        for_votes: Balance,
+        abstain_votes: Balance,
+    }
+
+    /// Result of a proposal, recorded once when it is finalized and never
+    /// recomputed, so later balance changes can't rewrite history.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Outcome {
+        for_votes: Balance,
+        against_votes: Balance,
+        abstain_votes: Balance,
+        /// Quorum in force when the proposal was finalized.
+        quorum_base: Balance,
+        passed: bool,
+        finalized_at: Timestamp,
+        /// Votes cast per strategy, the primary weight source first, after
+        /// multipliers. Empty for proposals weighed by the primary source only.
+        strategy_tallies: Vec<ProposalVote>,
+        /// Rounds and winner of a ranked-choice proposal.
+        runoff: Option<Runoff>,
+        /// Winners of an election, most approved first.
+        elected: Vec<AccountId>,
+    }
 
-        fn account_weight(&self, caller: AccountId) -> u8 {
-            let balance = PSP22Ref::balance_of(&self.governance_token, caller);
-            balance as u8
+    impl Outcome {
+        fn votes(&self) -> Votes {
+            Votes {
+                for_votes: self.for_votes,
+                against_votes: self.against_votes,
+                abstain_votes: self.abstain_votes,
+            }
         }
+    }
 
-        fn next_proposal_id(&mut self) -> ProposalId {
-            let id = self.next_proposal_id;
-            self.next_proposal_id += 1;
-            id
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VoteReceipt {
+        vote: VoteType,
+        weight: Balance,
+        cast_at: Timestamp,
+    }
+
+    /// Weight a delegator handed to `delegatee`. The weight is the delegator's
+    /// token balance when they last called `delegate`, less any decay applied
+    /// since.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Delegation {
+        delegatee: AccountId,
+        weight: Balance,
+        /// Weight and time of the last call to `delegate`, from which decay is
+        /// measured.
+        affirmed_weight: Balance,
+        affirmed_at: Timestamp,
+    }
+
+    /// A native contribution to the treasury through `fund`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Donation {
+        donor: AccountId,
+        amount: Balance,
+        memo: Option<Hash>,
+        funded_at: Timestamp,
+    }
+
+    /// Native balance or governance tokens locked as voting weight.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct NativeLock {
+        amount: Balance,
+        /// End of the latest voting period this lock was used in.
+        locked_until: Timestamp,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Shutdown {
+        successor: AccountId,
+        at: Timestamp,
+        /// Airdrop distributing the treasury, if it was split pro rata.
+        airdrop_id: Option<u32>,
+    }
+
+    /// A vote whose choice stays hidden until the proposal's ballot key is
+    /// revealed. The weight is fixed when the ballot is cast.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct EncryptedBallot {
+        voter: AccountId,
+        weight: Balance,
+        ciphertext: Vec<u8>,
+        cast_at: Timestamp,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Watchdog {
+        bond: Balance,
+        /// Proposal the watchdog paused whose review is still open.
+        pausing: Option<ProposalId>,
+    }
+
+    /// A watchdog's pause of a passed proposal, and the proposal reviewing it.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Pause {
+        watchdog: AccountId,
+        review: ProposalId,
+    }
+
+    /// A payout made ahead of a vote, awaiting ratification by its proposal.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct EmergencySpend {
+        spender: AccountId,
+        to: AccountId,
+        amount: Balance,
+        spent_at: Timestamp,
+    }
+
+    /// Periodic council elections. An election opens `election_lead` before the
+    /// current term ends and, once finalized, its winners replace the council
+    /// for `term_length`. Members keep their seats if an election fails.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CouncilSchedule {
+        seats: u32,
+        term_length: u64,
+        election_lead: u64,
+        /// Voting period of each election, in minutes.
+        voting_duration: u64,
+        nomination_period: u64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Election {
+        seats: u32,
+        nomination_period: u64,
+        /// In registration order, which also breaks ties in approvals.
+        candidates: Vec<AccountId>,
+    }
+
+    /// How a proposer's past proposals ended.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposerRecord {
+        executed: u32,
+        defeated: u32,
+    }
+
+    /// A nomination pool operation, SCALE-encoded as the input of the pool
+    /// staking chain extension.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    pub enum PoolCall {
+        Join { pool_id: u32, amount: Balance },
+        BondExtra(Balance),
+        Unbond(Balance),
+        WithdrawUnbonded,
+    }
+
+    /// The treasury's native funds by availability.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TreasuryBalances {
+        /// Available for payouts.
+        liquid: Balance,
+        /// Bonded in the governor's nomination pool.
+        staked: Balance,
+        /// Unbonding, withdrawable once the unbonding period has passed.
+        unbonding: Balance,
+    }
+
+    /// A contract call made while executing a proposal's actions. `output` is
+    /// the SCALE-encoded return value, cut off after `MAX_CALL_OUTPUT` bytes,
+    /// and empty if the call failed. Pool staking calls go to the runtime and
+    /// are logged with the governor as callee and the chain extension's
+    /// function id, big-endian, as selector.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CallRecord {
+        callee: AccountId,
+        selector: [u8; 4],
+        success: bool,
+        output: Vec<u8>,
+    }
+
+    /// Failed attempts to execute a passed proposal because the treasury
+    /// couldn't cover it.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ExecutionAttempts {
+        count: u32,
+        last_error: Option<GovernorError>,
+        last_attempt: Timestamp,
+        /// Until then further failures revert without being recorded.
+        next_retry_at: Timestamp,
+    }
+
+    /// Native deposit paid for the storage a proposal occupies.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct StorageDeposit {
+        payer: AccountId,
+        amount: Balance,
+    }
+
+    /// When a proposal went, or goes, through each stage of its lifecycle.
+    /// Later stages are filled in as the transitions happen.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Timeline {
+        created: Timestamp,
+        active: Timestamp,
+        end: Timestamp,
+        /// When the proposal was finalized as passed.
+        queued: Option<Timestamp>,
+        /// Earliest time the proposal can be executed.
+        eta: Timestamp,
+        executed: Option<Timestamp>,
+        canceled: Option<Timestamp>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ChangeKind {
+        ProposalCreated,
+        VoteCast,
+        ProposalFinalized,
+        ProposalExecuted,
+        ProposalCanceled,
+        PayoutClaimed,
+    }
+
+    /// One state transition in the changelog. `seq` increases by one per entry,
+    /// so a client can tell whether it missed entries that were overwritten.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ChangeEntry {
+        seq: u64,
+        kind: ChangeKind,
+        proposal_id: ProposalId,
+        at: Timestamp,
+    }
+
+    /// Storage a proposal id below `next_proposal_id` should have but doesn't,
+    /// reported by `integrity_check`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Inconsistency {
+        MissingProposal,
+        MissingVoteRecord,
+        MissingTimeline,
+        /// The proposal's category reference resolves to another id, or none.
+        ReferenceMismatch,
+    }
+
+    /// Everything about a proposal in one read.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ProposalFull {
+        proposal: Proposal,
+        text: Option<ProposalText>,
+        state: ProposalState,
+        /// Live tallies. They exclude the for and against addresses' balances,
+        /// which are added when the proposal is finalized.
+        votes: ProposalVote,
+        timeline: Option<Timeline>,
+        outcome: Option<Outcome>,
+    }
+
+    pub const STATUS_FINALIZED: u8 = 1 << 0;
+    pub const STATUS_EXECUTED: u8 = 1 << 1;
+    pub const STATUS_CANCELED: u8 = 1 << 2;
+
+    /// The mutable part of a proposal, kept in one small record so that every
+    /// state transition costs a single storage read and write. `Proposal` and
+    /// `ProposalText` are never rewritten after creation.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalStatus {
+        against_votes: Balance,
+        for_votes: Balance,
+        abstain_votes: Balance,
+        flags: u8,
+    }
+
+    impl ProposalStatus {
+        pub fn has(&self, flag: u8) -> bool {
+            self.flags & flag != 0
+        }
+
+        fn count(&mut self, vote: VoteType, weight: Balance) {
+            let votes = match vote {
+                VoteType::For => &mut self.for_votes,
+                VoteType::Against => &mut self.against_votes,
+                VoteType::Abstain => &mut self.abstain_votes,
+            };
+            *votes = votes.saturating_add(weight);
+        }
+
+        fn votes(&self) -> ProposalVote {
+            ProposalVote {
+                against_votes: self.against_votes,
+                for_votes: self.for_votes,
+                abstain_votes: self.abstain_votes,
+            }
+        }
+    }
+
+    pub type ProposalId = u32;
+
+    /// Memoizes PSP22 balance lookups for the duration of a single message, so
+    /// an account is never queried twice across contracts in one call.
+    #[derive(Default)]
+    struct WeightCache {
+        weights: BTreeMap<AccountId, Balance>,
+    }
+
+    impl WeightCache {
+        fn weight_of(&mut self, account: AccountId, fetch: impl FnOnce() -> Balance) -> Balance {
+            *self.weights.entry(account).or_insert_with(fetch)
+        }
+    }
+
+    #[ink(event)]
+    pub struct StrategyRegistered {
+        #[ink(topic)]
+        adapter: AccountId,
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct CodePinned {
+        #[ink(topic)]
+        contract: AccountId,
+        code_hash: Option<Hash>,
+    }
+
+    /// A pinned dependency runs code other than it was pinned to. `found` is
+    /// `None` if the contract no longer exists.
+    #[ink(event)]
+    pub struct DependencyCodeChanged {
+        #[ink(topic)]
+        contract: AccountId,
+        expected: Hash,
+        found: Option<Hash>,
+    }
+
+    #[ink(event)]
+    pub struct StrategyDeregistered {
+        #[ink(topic)]
+        adapter: AccountId,
+    }
+
+    /// A contract queried for voting weight failed, e.g. by trapping or
+    /// running out of gas, and counted as zero weight for `account`.
+    #[ink(event)]
+    pub struct WeightSourceFailed {
+        #[ink(topic)]
+        callee: AccountId,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        proposer: AccountId,
+        #[ink(topic)]
+        category: String,
+        vote_start: Timestamp,
+        vote_end: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ProposalSponsored {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        sponsor: AccountId,
+        sponsors: u32,
+    }
+
+    #[ink(event)]
+    pub struct SponsorStruck {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        sponsor: AccountId,
+        strikes: u32,
+    }
+
+    /// Voting on a proposal closes within `reminder_window`.
+    #[ink(event)]
+    pub struct VotingEndingSoon {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        vote_end: Timestamp,
+    }
+
+    /// A queued or draft proposal opened for voting.
+    #[ink(event)]
+    pub struct ProposalActivated {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        vote_start: Timestamp,
+        vote_end: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ProposalFinalized {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        passed: bool,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        executor: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCanceled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        canceler: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GuardianRotationScheduled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        incoming: AccountId,
+        outgoing: Option<AccountId>,
+        activates_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct GuardianRotationCanceled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        canceled_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleChanged {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        role: RoleType,
+        #[ink(topic)]
+        account: AccountId,
+        granted: bool,
+    }
+
+    #[ink(event)]
+    pub struct BondRefunded {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        proposer: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BondSlashed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        finalizer: AccountId,
+        bounty: Balance,
+        to_treasury: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ProposalFeeBurned {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        proposer: AccountId,
+        amount: Balance,
+        /// False if the token isn't burnable and the fee went to the treasury.
+        burned: bool,
+    }
+
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        voter: AccountId,
+        vote: VoteType,
+        weight: Balance,
+    }
+
+    #[ink(event)]
+    pub struct EncryptedVoteCast {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        voter: AccountId,
+        weight: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BallotKeyRevealed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        key: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct TreasuryStakeChanged {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        pool_id: u32,
+        staked: Balance,
+        unbonding: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TokensSwapped {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: Balance,
+        amount_out: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ExternalVoteCast {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        governor: AccountId,
+        external_proposal_id: u32,
+        vote: VoteType,
+    }
+
+    #[ink(event)]
+    pub struct TranslationAdded {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        locale: String,
+        content_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct CandidateRegistered {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        candidate: AccountId,
+        bond: Balance,
+    }
+
+    #[ink(event)]
+    pub struct CandidacyBondSettled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        candidate: AccountId,
+        amount: Balance,
+        refunded: bool,
+    }
+
+    #[ink(event)]
+    pub struct ExecutionFailed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        executor: AccountId,
+        error: GovernorError,
+        attempt: u32,
+    }
+
+    #[ink(event)]
+    pub struct EmergencySpent {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        spender: AccountId,
+        to: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct CouncilChanged {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        member: AccountId,
+        joined: bool,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        delegatee: Option<AccountId>,
+        weight: Balance,
+    }
+
+    #[ink(event)]
+    pub struct DelegateStatementSet {
+        #[ink(topic)]
+        delegate: AccountId,
+        statement: Hash,
+    }
+
+    #[ink(event)]
+    pub struct Cosigned {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        signer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct PayoutEscrowed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ActionsRevealed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        count: u32,
+    }
+
+    #[ink(event)]
+    pub struct EscrowAttested {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        arbiter: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct EscrowDisputed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        resolution_id: ProposalId,
+    }
+
+    #[ink(event)]
+    pub struct ReferendumLinked {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        index: u32,
+        /// Whether the governor submitted the referendum itself.
+        submitted: bool,
+    }
+
+    #[ink(event)]
+    pub struct WatchdogRegistered {
+        #[ink(topic)]
+        watchdog: AccountId,
+        bond: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ProposalPausedByWatchdog {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        watchdog: AccountId,
+        review_id: ProposalId,
+    }
+
+    /// A pause review ended. `upheld` pauses stay in force; lifted ones cost
+    /// the watchdog its bond.
+    #[ink(event)]
+    pub struct PauseReviewed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        watchdog: AccountId,
+        upheld: bool,
+    }
+
+    #[ink(event)]
+    pub struct DisbursementApproved {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        amount: Balance,
+        disbursable_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct DisbursementObjected {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        guardian: AccountId,
+        resolution_id: ProposalId,
+    }
+
+    #[ink(event)]
+    pub struct PayoutClaimed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PayoutClawedBack {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TreasuryFunded {
+        #[ink(topic)]
+        donation_id: u32,
+        #[ink(topic)]
+        donor: AccountId,
+        amount: Balance,
+        memo: Option<Hash>,
+    }
+
+    #[ink(event)]
+    pub struct GrantReportSubmitted {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        report: Hash,
+    }
+
+    #[ink(event)]
+    pub struct GrantClosed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        status: GrantStatus,
+    }
+
+    /// PSP22 tokens sent to the governor by someone else. `earmark` is the
+    /// category the sender set aside the tokens for, if any.
+    #[ink(event)]
+    pub struct TokensReceived {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+        earmark: Option<String>,
+    }
+
+    #[ink(event)]
+    pub struct TokensSwept {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TrancheReleased {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        index: u32,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct MilestonesCanceled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        unreleased: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TokenDeployed {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        token: AccountId,
+        total_supply: Balance,
+        migrated: bool,
+    }
+
+    #[ink(event)]
+    pub struct AirdropRegistered {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        airdrop_id: u32,
+        token: Option<AccountId>,
+        budget: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AirdropClosed {
+        #[ink(topic)]
+        airdrop_id: u32,
+        unclaimed: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AirdropClaimed {
+        #[ink(topic)]
+        airdrop_id: u32,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ProposalPruned {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        payer: AccountId,
+        refund: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ShutdownStarted {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        successor: AccountId,
+        treasury: Balance,
+        pro_rata: bool,
+    }
+
+    #[ink(event)]
+    pub struct GovernorMigrated {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        new_governor: AccountId,
+        open_proposals: Vec<ProposalId>,
+        tokens: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ParameterChanged {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        change: ParameterChange,
+    }
+
+    pub type Result<T> = core::result::Result<T, GovernorError>;
+
+    #[ink(storage)]
+    #[derive(Storage)]
+    pub struct VotingContract {
+        #[storage_field]
+        access: access_control::Data,
+        proposal_status: Mapping<ProposalId, ProposalStatus>,
+        receipts: Mapping<(ProposalId, AccountId), VoteReceipt>,
+        voting_history: Mapping<(AccountId, u32), ProposalId>,
+        voting_history_len: Mapping<AccountId, u32>,
+        delegations: Mapping<AccountId, Delegation>,
+        delegated_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        delegate_statements: Mapping<AccountId, Hash>,
+        delegate_list: Mapping<u32, AccountId>,
+        delegate_count: u32,
+        /// Accounts with the most delegated weight, heaviest first. Kept out of
+        /// the root cell, which every message loads.
+        top_delegates: Lazy<Vec<(AccountId, Balance)>>,
+        /// Added to the block timestamp, never lowered. Only settable in
+        /// `test-utils` builds.
+        time_offset: u64,
+        native_locks: Mapping<AccountId, NativeLock>,
+        lock_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        /// Governance tokens locked through `lock_tokens`.
+        token_locks: Mapping<AccountId, NativeLock>,
+        token_lock_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        /// Governance tokens backing each account's registered LP positions, as
+        /// recorded by `checkpoint_lp_weight`.
+        lp_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        total_tokens_locked: Balance,
+        /// PSP22 tokens received through transfers, by token and earmark. The
+        /// empty earmark is the general treasury.
+        token_deposits: Mapping<(AccountId, String), Balance>,
+        donations: Mapping<u32, Donation>,
+        donation_count: u32,
+        donor_totals: Mapping<AccountId, Balance>,
+        /// Native funds received through `fund`, in total.
+        total_funded: Balance,
+        grants: Mapping<ProposalId, GrantRecord>,
+        governance_token_info: TokenInfo,
+        watchdogs: Mapping<AccountId, Watchdog>,
+        pauses: Mapping<ProposalId, Pause>,
+        /// Proposals paused by a watchdog, by the proposal reviewing the pause.
+        pause_reviews: Mapping<ProposalId, ProposalId>,
+        /// Runtime referendum index linked to each executed proposal.
+        referenda: Mapping<ProposalId, u32>,
+        allowed_voter_code: Mapping<Hash, ()>,
+        /// Next nonce of each account's signed operations.
+        nonces: Mapping<AccountId, u64>,
+        /// Registered strategy adapters and the code hash each is pinned to.
+        strategy_adapters: Mapping<AccountId, Hash>,
+        /// Code hashes other cross-contract dependencies are pinned to.
+        pinned_code: Mapping<AccountId, Hash>,
+        total_locked: Balance,
+        proposals: Mapping<ProposalId, Proposal>,
+        proposal_texts: Mapping<ProposalId, ProposalText>,
+        proposal_actions: Mapping<ProposalId, Vec<ProposalAction>>,
+        next_proposal_id: u32,
+        /// Proposals neither finalized nor canceled, packed into slots
+        /// `0..open_proposal_count` so they can be listed without scanning
+        /// every proposal.
+        open_proposals: Mapping<u32, ProposalId>,
+        open_proposal_slots: Mapping<ProposalId, u32>,
+        open_proposal_count: u32,
+        category_counters: Mapping<String, u32>,
+        references: Mapping<(String, u32), ProposalId>,
+        category_templates: Mapping<String, CategoryTemplate>,
+        /// Kept out of the root cell so that only cosigning loads the list.
+        cosigners: Lazy<Vec<AccountId>>,
+        cosignatures: Mapping<(ProposalId, AccountId), ()>,
+        outcomes: Mapping<ProposalId, Outcome>,
+        milestone_plans: Mapping<ProposalId, MilestonePlan>,
+        escrows: Mapping<ProposalId, Escrow>,
+        /// Disputed escrows, by the proposal resolving the dispute.
+        escrow_disputes: Mapping<ProposalId, ProposalId>,
+        bonds: Mapping<ProposalId, Balance>,
+        /// Bonds of passed proposals waiting for `reclaim_bond`.
+        bonds_owed: Mapping<AccountId, Balance>,
+        total_bonded: Balance,
+        changelog: Mapping<u64, ChangeEntry>,
+        changelog_head: u64,
+        total_escrowed: Balance,
+        airdrops: Mapping<u32, Airdrop>,
+        airdrop_claims: Mapping<(u32, AccountId), ()>,
+        next_airdrop_id: u32,
+        /// Unclaimed native budget of open airdrops.
+        total_airdrop_reserved: Balance,
+        /// Unclaimed token budget of open airdrops, by token.
+        airdrop_reserved_tokens: Mapping<AccountId, Balance>,
+        /// Number of tokens with a nonzero entry in `airdrop_reserved_tokens`.
+        airdrop_reserved_token_count: u32,
+        lp_pools: Vec<LpPool>,
+        strategies: Vec<Strategy>,
+        proposal_strategies: Mapping<ProposalId, Vec<Strategy>>,
+        strategy_tallies: Mapping<ProposalId, Vec<ProposalVote>>,
+        timelines: Mapping<ProposalId, Timeline>,
+        storage_deposits: Mapping<ProposalId, StorageDeposit>,
+        /// Pruned proposals' deposits waiting for `reclaim_storage`.
+        storage_owed: Mapping<AccountId, Balance>,
+        total_storage_deposits: Balance,
+        shutdown: Option<Shutdown>,
+        /// Proposals open for voting, at most `max_active_proposals`.
+        active_proposals: Vec<ProposalId>,
+        pending_queue: Mapping<u32, ProposalId>,
+        pending_head: u32,
+        pending_tail: u32,
+        /// Voting durations of queued proposals, in minutes. A proposal is
+        /// pending exactly while it has an entry here.
+        pending_durations: Mapping<ProposalId, u64>,
+        /// Draft proposals, which are also pending, by the earliest time their
+        /// proposer can activate them.
+        drafts: Mapping<ProposalId, Timestamp>,
+        sponsors: Mapping<ProposalId, Vec<AccountId>>,
+        sponsor_strikes: Mapping<AccountId, u32>,
+        /// Guardian rotations waiting for their handover, by proposal.
+        guardian_handovers: Mapping<ProposalId, GuardianHandover>,
+        /// Proposals `poke` already emitted a reminder for.
+        reminded: Mapping<ProposalId, ()>,
+        proposer_records: Mapping<AccountId, ProposerRecord>,
+        content_hashes: Mapping<ProposalId, Hash>,
+        /// When a proposal with the given content hash was last defeated.
+        defeated_at: Mapping<Hash, Timestamp>,
+        /// Decryption function of each proposal taking encrypted ballots.
+        encrypted_proposals: Mapping<ProposalId, u32>,
+        encrypted_ballots: Mapping<(ProposalId, u32), EncryptedBallot>,
+        encrypted_ballot_count: Mapping<ProposalId, u32>,
+        encrypted_voters: Mapping<(ProposalId, AccountId), ()>,
+        ballot_keys: Mapping<ProposalId, Vec<u8>>,
+        ballots_tallied: Mapping<ProposalId, u32>,
+        /// Number of options of each ranked-choice proposal.
+        ranked_options: Mapping<ProposalId, u8>,
+        ranked_ballots: Mapping<(ProposalId, AccountId), Vec<u8>>,
+        /// Weight cast per distinct ranking, so the runoff cost doesn't grow with
+        /// the number of voters.
+        ranked_tallies: Mapping<ProposalId, Vec<(Vec<u8>, Balance)>>,
+        elections: Mapping<ProposalId, Election>,
+        /// Approved weight per candidate, in the order of `Election::candidates`.
+        approvals: Mapping<ProposalId, Vec<Balance>>,
+        approval_ballots: Mapping<(ProposalId, AccountId), Vec<u32>>,
+        candidacy_bonds: Mapping<(ProposalId, AccountId), Balance>,
+        council_schedule: Option<CouncilSchedule>,
+        council: Vec<AccountId>,
+        council_term_end: Timestamp,
+        /// Council election in progress, if any.
+        council_election: Option<ProposalId>,
+        emergency_spends: Mapping<ProposalId, EmergencySpend>,
+        /// Ratification proposal of the emergency spend not yet finalized.
+        pending_emergency: Option<ProposalId>,
+        failed_executions: Mapping<ProposalId, ExecutionAttempts>,
+        execution_logs: Mapping<ProposalId, Vec<CallRecord>>,
+        /// Hashes of the translated title and description, by locale.
+        translations: Mapping<ProposalId, Vec<(String, Hash)>>,
+        /// Nomination pool the treasury is a member of, if any.
+        staking_pool: Option<u32>,
+        total_staked: Balance,
+        total_unbonding: Balance,
+        config: GovernorConfig,
+    }
+
+    /// Queries the runtime for the amount `account` has staked. The chain extension
+    /// is expected to take an `AccountId` and return a `Balance`.
+    fn staked_balance(func_id: u32, account: AccountId) -> Balance {
+        ink::env::chain_extension::ChainExtensionMethod::build(func_id)
+            .input::<AccountId>()
+            .output::<Balance, false>()
+            .ignore_error_code()
+            .call(&account)
+    }
+
+    /// Asks the runtime to send an XCM `message` to `dest`. The chain extension is
+    /// expected to take both as encoded bytes and return whether dispatch succeeded.
+    fn send_xcm(func_id: u32, dest: &[u8], message: &[u8]) -> bool {
+        ink::env::chain_extension::ChainExtensionMethod::build(func_id)
+            .input::<(Vec<u8>, Vec<u8>)>()
+            .output::<bool, false>()
+            .ignore_error_code()
+            .call(&(dest.to_vec(), message.to_vec()))
+    }
+
+    /// Asks the runtime to note `call` as a preimage and submit it as a referendum
+    /// on `track`. The chain extension is expected to take both and return the
+    /// referendum index, or `None` if submission failed.
+    fn submit_referendum(func_id: u32, track: u16, call: &[u8]) -> Option<u32> {
+        ink::env::chain_extension::ChainExtensionMethod::build(func_id)
+            .input::<(u16, Vec<u8>)>()
+            .output::<Option<u32>, false>()
+            .ignore_error_code()
+            .call(&(track, call.to_vec()))
+    }
+
+    /// Asks the runtime to decrypt `ciphertext` with a proposal's revealed `key`.
+    /// The chain extension is expected to take both as bytes and return the vote,
+    /// or `None` if the ballot is malformed.
+    fn decrypt_ballot(func_id: u32, key: &[u8], ciphertext: &[u8]) -> Option<VoteType> {
+        ink::env::chain_extension::ChainExtensionMethod::build(func_id)
+            .input::<(Vec<u8>, Vec<u8>)>()
+            .output::<Option<VoteType>, false>()
+            .ignore_error_code()
+            .call(&(key.to_vec(), ciphertext.to_vec()))
+    }
+
+    /// Asks the runtime to perform a nomination pool operation for the governor.
+    /// The chain extension is expected to take a `PoolCall` and return whether
+    /// it succeeded.
+    fn pool_staking(func_id: u32, call: PoolCall) -> bool {
+        ink::env::chain_extension::ChainExtensionMethod::build(func_id)
+            .input::<PoolCall>()
+            .output::<bool, false>()
+            .ignore_error_code()
+            .call(&call)
+    }
+
+    enum CheckpointKind {
+        Delegated,
+        Lock,
+        TokenLock,
+        Lp,
+    }
+
+    impl AccessControl for VotingContract {}
+
+    /// Credits PSP22 tokens sent to the governor to the treasury ledger. The
+    /// transfer's `data` may name a category, UTF-8 encoded, to earmark the
+    /// tokens for; other data rejects the transfer. Transfers the governor
+    /// makes itself, such as locks, aren't deposits and are left out.
+    impl PSP22Receiver for VotingContract {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22ReceiverError> {
+            if operator == self.env().account_id() {
+                return Ok(());
+            }
+            let earmark = match String::from_utf8(data) {
+                Ok(earmark) if earmark.is_empty() => None,
+                Ok(earmark) if Self::is_valid_category(&earmark) => Some(earmark),
+                _ => {
+                    return Err(PSP22ReceiverError::TransferRejected(
+                        "earmark must be a category".to_string(),
+                    ))
+                }
+            };
+
+            let token = self.env().caller();
+            let key = (token, earmark.clone().unwrap_or_default());
+            let deposited = self.token_deposits.get(&key).unwrap_or(0);
+            self.token_deposits
+                .insert(&key, &deposited.saturating_add(value));
+            self.env().emit_event(TokensReceived {
+                token,
+                from,
+                amount: value,
+                earmark,
+            });
+
+            Ok(())
+        }
+    }
+
+    /// Display metadata of the governance token, read through `PSP22Metadata`.
+    /// Fields the token doesn't provide are `None`.
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TokenInfo {
+        name: Option<String>,
+        symbol: Option<String>,
+        decimals: Option<u8>,
+    }
+
+    /// State beyond the configuration that a redeployment can start from, as
+    /// returned by `export_state`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct InitialState {
+        pub council: Vec<AccountId>,
+        pub council_term_end: Timestamp,
+        pub council_schedule: Option<CouncilSchedule>,
+        pub cosigners: Vec<AccountId>,
+        pub strategies: Vec<Strategy>,
+    }
+
+    impl VotingContract {
+        /// Deploys a governor mirroring another one, e.g. a production
+        /// deployment on a testnet, from its `export_config` and, optionally,
+        /// its `export_state`.
+        #[ink(constructor)]
+        pub fn new_with_state(config: GovernorConfig, state: Option<InitialState>) -> Self {
+            let mut instance = Self::new(config);
+            if let Some(state) = state {
+                for member in state.council.iter() {
+                    instance._setup_role(COUNCIL_ROLE, *member);
+                }
+                instance.council = state.council;
+                instance.council_term_end = state.council_term_end;
+                instance.council_schedule = state.council_schedule;
+                instance.cosigners.set(&state.cosigners);
+                instance.strategies = state.strategies;
+            }
+
+            instance
+        }
+
+        #[ink(constructor)]
+        pub fn new(config: GovernorConfig) -> Self {
+            let mut instance = Self {
+                access: Default::default(),
+                proposal_status: Mapping::new(),
+                receipts: Mapping::new(),
+                voting_history: Mapping::new(),
+                voting_history_len: Mapping::new(),
+                delegations: Mapping::new(),
+                delegated_checkpoints: Mapping::new(),
+                delegate_statements: Mapping::new(),
+                delegate_list: Mapping::new(),
+                delegate_count: 0,
+                top_delegates: Lazy::new(),
+                time_offset: 0,
+                native_locks: Mapping::new(),
+                lock_checkpoints: Mapping::new(),
+                token_locks: Mapping::new(),
+                token_lock_checkpoints: Mapping::new(),
+                lp_checkpoints: Mapping::new(),
+                total_tokens_locked: 0,
+                token_deposits: Mapping::new(),
+                donations: Mapping::new(),
+                donation_count: 0,
+                donor_totals: Mapping::new(),
+                total_funded: 0,
+                grants: Mapping::new(),
+                governance_token_info: TokenInfo::default(),
+                watchdogs: Mapping::new(),
+                pauses: Mapping::new(),
+                pause_reviews: Mapping::new(),
+                referenda: Mapping::new(),
+                allowed_voter_code: Mapping::new(),
+                nonces: Mapping::new(),
+                strategy_adapters: Mapping::new(),
+                pinned_code: Mapping::new(),
+                total_locked: 0,
+                proposals: Mapping::new(),
+                proposal_texts: Mapping::new(),
+                proposal_actions: Mapping::new(),
+                next_proposal_id: 0,
+                open_proposals: Mapping::new(),
+                open_proposal_slots: Mapping::new(),
+                open_proposal_count: 0,
+                category_counters: Mapping::new(),
+                references: Mapping::new(),
+                category_templates: Mapping::new(),
+                cosigners: Lazy::new(),
+                cosignatures: Mapping::new(),
+                outcomes: Mapping::new(),
+                milestone_plans: Mapping::new(),
+                escrows: Mapping::new(),
+                escrow_disputes: Mapping::new(),
+                bonds: Mapping::new(),
+                bonds_owed: Mapping::new(),
+                total_bonded: 0,
+                changelog: Mapping::new(),
+                changelog_head: 0,
+                total_escrowed: 0,
+                airdrops: Mapping::new(),
+                airdrop_claims: Mapping::new(),
+                next_airdrop_id: 0,
+                total_airdrop_reserved: 0,
+                airdrop_reserved_tokens: Mapping::new(),
+                airdrop_reserved_token_count: 0,
+                lp_pools: Vec::new(),
+                strategies: Vec::new(),
+                proposal_strategies: Mapping::new(),
+                strategy_tallies: Mapping::new(),
+                timelines: Mapping::new(),
+                storage_deposits: Mapping::new(),
+                storage_owed: Mapping::new(),
+                total_storage_deposits: 0,
+                shutdown: None,
+                active_proposals: Vec::new(),
+                pending_queue: Mapping::new(),
+                pending_head: 0,
+                pending_tail: 0,
+                pending_durations: Mapping::new(),
+                drafts: Mapping::new(),
+                sponsors: Mapping::new(),
+                sponsor_strikes: Mapping::new(),
+                reminded: Mapping::new(),
+                guardian_handovers: Mapping::new(),
+                proposer_records: Mapping::new(),
+                content_hashes: Mapping::new(),
+                defeated_at: Mapping::new(),
+                encrypted_proposals: Mapping::new(),
+                encrypted_ballots: Mapping::new(),
+                encrypted_ballot_count: Mapping::new(),
+                encrypted_voters: Mapping::new(),
+                ballot_keys: Mapping::new(),
+                ballots_tallied: Mapping::new(),
+                ranked_options: Mapping::new(),
+                ranked_ballots: Mapping::new(),
+                ranked_tallies: Mapping::new(),
+                elections: Mapping::new(),
+                approvals: Mapping::new(),
+                approval_ballots: Mapping::new(),
+                candidacy_bonds: Mapping::new(),
+                council_schedule: None,
+                council: Vec::new(),
+                council_term_end: 0,
+                council_election: None,
+                emergency_spends: Mapping::new(),
+                pending_emergency: None,
+                failed_executions: Mapping::new(),
+                execution_logs: Mapping::new(),
+                translations: Mapping::new(),
+                staking_pool: None,
+                total_staked: 0,
+                total_unbonding: 0,
+                config,
+            };
+
+            let this = instance.env().account_id();
+            let caller = instance.env().caller();
+            instance._init_with_admin(this);
+            instance._setup_role(PROPOSER_ROLE, caller);
+            instance._setup_role(EXECUTOR_ROLE, caller);
+            instance._setup_role(GUARDIAN_ROLE, caller);
+            instance.refresh_token_info();
+            instance.pin_governance_token();
+
+            instance
+        }
+
+        #[ink(message, payable, selector = 0xCB14B5B5)]
+        pub fn propose(
+            &mut self,
+            for_address: AccountId,
+            against_address: AccountId,
+            to: AccountId,
+            title: String,
+            description: String,
+            category: String,
+            amount: Balance,
+            duration: u64,
+            actions: Vec<ProposalAction>,
+        ) -> Result<()> {
+            let request = ProposalRequest {
+                for_address,
+                against_address,
+                to,
+                title,
+                description,
+                category,
+                amount,
+                duration,
+                actions,
+            };
+            self.submit_proposal(self.env().caller(), request)
+        }
+
+        /// Submits a proposal signed off-chain by `proposer`, so a relayer can
+        /// pay the fees of a holder without native balance. The signed payload
+        /// is `request` (see the `signature` module). The relayer pays the bond
+        /// and storage deposit; the deposit is owed back to the relayer, the
+        /// bond is returned to the proposer.
+        #[ink(message, payable, selector = 0x8019427B)]
+        pub fn propose_by_sig(
+            &mut self,
+            proposer: AccountId,
+            request: ProposalRequest,
+            nonce: u64,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            let signed = Signed {
+                operation: Operation::Propose,
+                nonce,
+                deadline,
+                signature,
+            };
+            self.use_signature(proposer, &request, signed)?;
+            self.submit_proposal(proposer, request)
+        }
+
+        /// Casts `voter`'s ballot through a relayer. The signed payload is
+        /// `(proposal_id, vote)` (see the `signature` module).
+        #[ink(message, selector = 0x180EF53F)]
+        pub fn vote_by_sig(
+            &mut self,
+            voter: AccountId,
+            proposal_id: ProposalId,
+            vote: VoteType,
+            nonce: u64,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            let signed = Signed {
+                operation: Operation::Vote,
+                nonce,
+                deadline,
+                signature,
+            };
+            self.use_signature(voter, &(proposal_id, vote), signed)?;
+            self.cast_vote(voter, proposal_id, vote)
+        }
+
+        /// Domain separator every signed operation of this governor commits to.
+        #[ink(message, selector = 0xB7F73B4A)]
+        pub fn domain_separator(&self) -> Hash {
+            Hash::from(signature::domain_separator(
+                self.env().account_id().as_ref(),
+            ))
+        }
+
+        /// Next nonce `account` has to sign an off-chain operation with. Nonces
+        /// are shared by all operations and used up in order.
+        #[ink(message, selector = 0xD9A8C128)]
+        pub fn get_nonce(&self, account: AccountId) -> u64 {
+            self.nonces.get(account).unwrap_or(0)
+        }
+
+        /// Casts the caller's snapshot weight (see `weight_of_at`) on a proposal. The
+        /// weight is added to the running tally right away, so finalizing and
+        /// executing never have to revisit individual voters.
+        #[ink(message, selector = 0x083BE260)]
+        pub fn vote(&mut self, proposal_id: ProposalId, vote: VoteType) -> Result<()> {
+            self.cast_vote(self.env().caller(), proposal_id, vote)
+        }
+
+        /// Ranks the options of a ranked-choice proposal, most preferred first, as
+        /// indices into its `Options` action. Unranked options get no support
+        /// from this ballot. The weight counts towards quorum as a for vote. A
+        /// ranking nobody has cast yet needs `1 / MAX_DISTINCT_RANKINGS` of the
+        /// quorum behind it.
+        #[ink(message, selector = 0x66E05BEB)]
+        pub fn vote_ranked(&mut self, proposal_id: ProposalId, ranking: Vec<u8>) -> Result<()> {
+            let options = self
+                .ranked_options
+                .get(proposal_id)
+                .ok_or(GovernorError::NotRankedChoice)?;
+            if self.encrypted_proposals.contains(proposal_id) {
+                return Err(GovernorError::BallotsEncrypted);
+            }
+            if ranking.is_empty() {
+                return Err(GovernorError::InvalidRanking);
+            }
+            let mut seen = 0u32;
+            for option in ranking.iter() {
+                if *option >= options || seen & (1u32 << option) != 0 {
+                    return Err(GovernorError::InvalidRanking);
+                }
+                seen |= 1u32 << option;
+            }
+            let voter = self.env().caller();
+            let (mut status, weights) = self.open_ballot(proposal_id, voter)?;
+            let weight = weights
+                .iter()
+                .fold(0, |total: Balance, w| total.saturating_add(*w));
+
+            let mut tallies = self.ranked_tallies.get(proposal_id).unwrap_or_default();
+            match tallies
+                .iter_mut()
+                .find(|(existing, _)| *existing == ranking)
+            {
+                Some((_, total)) => *total = total.saturating_add(weight),
+                None if tallies.len() < MAX_DISTINCT_RANKINGS as usize => {
+                    // Opening a new ranking takes a share of the quorum, so dust
+                    // ballots can't use up the slots and lock out later voters.
+                    let quorum = self
+                        .proposals
+                        .get(proposal_id)
+                        .map_or(0, |proposal| proposal.quorum);
+                    let need = (quorum / MAX_DISTINCT_RANKINGS as Balance)
+                        .max(self.config.min_vote_weight);
+                    if weight < need {
+                        return Err(GovernorError::VoteWeightBelowMinimum { have: weight, need });
+                    }
+                    tallies.push((ranking.clone(), weight))
+                }
+                None => return Err(GovernorError::TooManyRankings),
+            }
+            self.ranked_tallies.insert(proposal_id, &tallies);
+            self.ranked_ballots.insert((proposal_id, voter), &ranking);
+            status.count(VoteType::For, weight);
+            self.proposal_status.insert(proposal_id, &status);
+
+            self.run_hook(hooks::ON_VOTE, (proposal_id, voter, weight))?;
+            self.record_change(ChangeKind::VoteCast, proposal_id);
+            self.env().emit_event(VoteCast {
+                proposal_id,
+                voter,
+                vote: VoteType::For,
+                weight,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message, selector = 0x461B92A1)]
+        pub fn get_ranked_ballot(
+            &self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+        ) -> Option<Vec<u8>> {
+            self.ranked_ballots.get((proposal_id, voter))
+        }
+
+        /// Stands the caller for election, sending `candidacy_bond` along. Open
+        /// during the election's nomination period.
+        #[ink(message, payable, selector = 0x653D424F)]
+        pub fn register_candidate(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.activate_queued();
+            let mut election = self
+                .elections
+                .get(proposal_id)
+                .ok_or(GovernorError::NotElection)?;
+            if self.pending_durations.contains(proposal_id) {
+                return Err(GovernorError::ProposalPending);
+            }
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+            let nomination_end = self.nomination_end(proposal_id, &election);
+            if self.now() >= nomination_end {
+                return Err(GovernorError::NominationClosed);
+            }
+
+            let candidate = self.env().caller();
+            if election.candidates.contains(&candidate) {
+                return Err(GovernorError::AlreadyCandidate);
+            }
+            if election.candidates.len() >= MAX_CANDIDATES as usize {
+                return Err(GovernorError::TooManyCandidates);
+            }
+            let bond = self.env().transferred_value();
+            if bond != self.config.candidacy_bond {
+                return Err(GovernorError::IncorrectBond);
+            }
+
+            election.candidates.push(candidate);
+            self.elections.insert(proposal_id, &election);
+            if bond > 0 {
+                self.candidacy_bonds.insert((proposal_id, candidate), &bond);
+                self.total_bonded += bond;
+            }
+
+            self.env().emit_event(CandidateRegistered {
+                proposal_id,
+                candidate,
+                bond,
+            });
+
+            Ok(())
+        }
+
+        /// Approves the given candidates, as indices into the election's
+        /// candidate list. Each approved candidate receives the caller's full
+        /// weight, which also counts towards quorum as a for vote.
+        #[ink(message, selector = 0x08CA6C84)]
+        pub fn vote_approval(
+            &mut self,
+            proposal_id: ProposalId,
+            candidates: Vec<u32>,
+        ) -> Result<()> {
+            let election = self
+                .elections
+                .get(proposal_id)
+                .ok_or(GovernorError::NotElection)?;
+            if self.encrypted_proposals.contains(proposal_id) {
+                return Err(GovernorError::BallotsEncrypted);
+            }
+            if self.now() < self.nomination_end(proposal_id, &election) {
+                return Err(GovernorError::NominationOpen);
+            }
+            let mut sorted = candidates.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            if candidates.is_empty()
+                || sorted.len() != candidates.len()
+                || sorted
+                    .iter()
+                    .any(|index| *index as usize >= election.candidates.len())
+            {
+                return Err(GovernorError::InvalidApprovals);
+            }
+            let voter = self.env().caller();
+            let (mut status, weights) = self.open_ballot(proposal_id, voter)?;
+            let weight = weights
+                .iter()
+                .fold(0, |total: Balance, w| total.saturating_add(*w));
+
+            let mut approvals = self.approvals.get(proposal_id).unwrap_or_default();
+            approvals.resize(election.candidates.len(), 0);
+            for index in candidates.iter() {
+                let approved = &mut approvals[*index as usize];
+                *approved = approved.saturating_add(weight);
+            }
+            self.approvals.insert(proposal_id, &approvals);
+            self.approval_ballots
+                .insert((proposal_id, voter), &candidates);
+            status.count(VoteType::For, weight);
+            self.proposal_status.insert(proposal_id, &status);
+
+            self.run_hook(hooks::ON_VOTE, (proposal_id, voter, weight))?;
+            self.record_change(ChangeKind::VoteCast, proposal_id);
+            self.env().emit_event(VoteCast {
+                proposal_id,
+                voter,
+                vote: VoteType::For,
+                weight,
+            });
+
+            Ok(())
+        }
+
+        /// Returns a finalized election's bond to the caller if they received any
+        /// approvals. The bond of a candidate nobody approved goes to the treasury.
+        #[ink(message, selector = 0xE21D1042)]
+        pub fn settle_candidacy(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let candidate = self.env().caller();
+            let election = self
+                .elections
+                .get(proposal_id)
+                .ok_or(GovernorError::NotElection)?;
+            let index = election
+                .candidates
+                .iter()
+                .position(|account| *account == candidate)
+                .ok_or(GovernorError::NotCandidate)?;
+            if !self.outcomes.contains(proposal_id) {
+                return Err(GovernorError::VotePeriodNotEnded {
+                    ends_at: self.proposals.get(proposal_id).map_or(0, |p| p.vote_end),
+                    now: self.now(),
+                });
+            }
+            let amount = self
+                .candidacy_bonds
+                .get((proposal_id, candidate))
+                .ok_or(GovernorError::NothingToReclaim)?;
+
+            self.candidacy_bonds.remove((proposal_id, candidate));
+            self.total_bonded -= amount;
+            let approvals = self.approvals.get(proposal_id).unwrap_or_default();
+            let refunded = approvals.get(index).map_or(false, |approved| *approved > 0);
+            if refunded {
+                self.env()
+                    .transfer(candidate, amount)
+                    .map_err(|_| GovernorError::TransferError)?;
+            }
+
+            self.env().emit_event(CandidacyBondSettled {
+                proposal_id,
+                candidate,
+                amount,
+                refunded,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the council members, the end of their term and the council
+        /// election in progress, if any.
+        #[ink(message, selector = 0x60FADEAA)]
+        pub fn get_council(&self) -> (Vec<AccountId>, Timestamp, Option<ProposalId>) {
+            (
+                self.council.clone(),
+                self.council_term_end,
+                self.council_election,
+            )
+        }
+
+        #[ink(message, selector = 0x0B9021F5)]
+        pub fn get_election(&self, proposal_id: ProposalId) -> Option<Election> {
+            self.elections.get(proposal_id)
+        }
+
+        /// Returns `(candidate, approved weight)` for every candidate of an
+        /// election, most approved first. The first `seats` entries are elected.
+        #[ink(message, selector = 0xF848E913)]
+        pub fn get_election_results(&self, proposal_id: ProposalId) -> Vec<(AccountId, Balance)> {
+            match self.elections.get(proposal_id) {
+                Some(election) => self.election_ranking(proposal_id, &election),
+                None => Vec::new(),
+            }
+        }
+
+        /// Casts an encrypted ballot on a proposal created while ballot encryption
+        /// was enabled. Its weight counts once the committee has revealed the
+        /// proposal's key and the ballot is tallied through `tally_ballots`.
+        #[ink(message, selector = 0xEE55F612)]
+        pub fn vote_encrypted(
+            &mut self,
+            proposal_id: ProposalId,
+            ciphertext: Vec<u8>,
+        ) -> Result<()> {
+            if !self.encrypted_proposals.contains(proposal_id) {
+                return Err(GovernorError::BallotsNotEncrypted);
+            }
+            if ciphertext.len() > MAX_CIPHERTEXT_SIZE as usize {
+                return Err(GovernorError::PayloadTooLarge);
+            }
+            let voter = self.env().caller();
+            let (_, weights) = self.open_ballot(proposal_id, voter)?;
+            let weight = weights
+                .iter()
+                .fold(0, |total: Balance, w| total.saturating_add(*w));
+
+            let index = self.encrypted_ballot_count.get(proposal_id).unwrap_or(0);
+            let ballot = EncryptedBallot {
+                voter,
+                weight,
+                ciphertext,
+                cast_at: self.now(),
+            };
+            self.encrypted_ballots.insert((proposal_id, index), &ballot);
+            self.encrypted_ballot_count
+                .insert(proposal_id, &(index + 1));
+            self.encrypted_voters.insert((proposal_id, voter), &());
+
+            self.run_hook(hooks::ON_VOTE, (proposal_id, voter, weight))?;
+            self.record_change(ChangeKind::VoteCast, proposal_id);
+            self.env().emit_event(EncryptedVoteCast {
+                proposal_id,
+                voter,
+                weight,
+            });
+
+            Ok(())
+        }
+
+        /// Publishes the key decrypting a proposal's ballots once voting has ended,
+        /// which lets anyone tally and verify them. Without a key by the end of
+        /// `BALLOT_REVEAL_PERIOD` the proposal fails.
+        #[ink(message, selector = 0x6C132964)]
+        pub fn reveal_ballot_key(&mut self, proposal_id: ProposalId, key: Vec<u8>) -> Result<()> {
+            self.ensure_role(COMMITTEE_ROLE)?;
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            if !self.encrypted_proposals.contains(proposal_id) {
+                return Err(GovernorError::BallotsNotEncrypted);
+            }
+            let now = self.now();
+            if self.pending_durations.contains(proposal_id) || now < proposal.vote_end {
+                return Err(GovernorError::VotePeriodNotEnded {
+                    ends_at: proposal.vote_end,
+                    now,
+                });
+            }
+            if self.ballot_keys.contains(proposal_id) {
+                return Err(GovernorError::BallotKeyAlreadyRevealed);
+            }
+
+            self.ballot_keys.insert(proposal_id, &key);
+            self.env()
+                .emit_event(BallotKeyRevealed { proposal_id, key });
+
+            Ok(())
+        }
+
+        /// Decrypts and counts up to `limit` further ballots of a proposal, at most
+        /// `MAX_PAGE_SIZE`. Ballots that don't decrypt to a vote are skipped.
+        /// Callable by anyone once the key is revealed; the proposal can be
+        /// finalized after every ballot is tallied.
+        #[ink(message, selector = 0x09277A36)]
+        pub fn tally_ballots(&mut self, proposal_id: ProposalId, limit: u32) -> Result<u32> {
+            let func_id = self
+                .encrypted_proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::BallotsNotEncrypted)?;
+            let key = self
+                .ballot_keys
+                .get(proposal_id)
+                .ok_or(GovernorError::BallotKeyNotRevealed)?;
+
+            let mut status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_FINALIZED) {
+                return Err(GovernorError::ProposalAlreadyFinalized);
+            }
+            let count = self.encrypted_ballot_count.get(proposal_id).unwrap_or(0);
+            let start = self.ballots_tallied.get(proposal_id).unwrap_or(0);
+            let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+            for index in start..end {
+                let ballot = match self.encrypted_ballots.get((proposal_id, index)) {
+                    Some(ballot) => ballot,
+                    None => continue,
+                };
+                let vote = match decrypt_ballot(func_id, &key, &ballot.ciphertext) {
+                    Some(vote) => vote,
+                    None => continue,
+                };
+                status.count(vote, ballot.weight);
+                let receipt = VoteReceipt {
+                    vote,
+                    weight: ballot.weight,
+                    cast_at: ballot.cast_at,
+                };
+                self.record_receipt(proposal_id, ballot.voter, &receipt);
+            }
+            self.proposal_status.insert(proposal_id, &status);
+            self.ballots_tallied.insert(proposal_id, &end);
+
+            Ok(count - end)
+        }
+
+        #[ink(message, selector = 0x29B6C80A)]
+        pub fn get_encrypted_ballot(
+            &self,
+            proposal_id: ProposalId,
+            index: u32,
+        ) -> Option<EncryptedBallot> {
+            self.encrypted_ballots.get((proposal_id, index))
+        }
+
+        /// Returns `(ballots cast, ballots tallied, revealed key)` of a proposal
+        /// taking encrypted ballots.
+        #[ink(message, selector = 0x0180CCE6)]
+        pub fn get_ballot_tally(&self, proposal_id: ProposalId) -> (u32, u32, Option<Vec<u8>>) {
+            (
+                self.encrypted_ballot_count.get(proposal_id).unwrap_or(0),
+                self.ballots_tallied.get(proposal_id).unwrap_or(0),
+                self.ballot_keys.get(proposal_id),
+            )
+        }
+
+        /// Locks the transferred native value as voting weight. Only available when
+        /// the governor weighs votes by native balance.
+        #[ink(message, payable, selector = 0xBBAA6540)]
+        pub fn lock(&mut self) -> Result<()> {
+            if !self.uses_source(WeightSource::Native) {
+                return Err(GovernorError::UnsupportedWeightSource);
+            }
+
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(GovernorError::AmountShouldNotBeZero);
+            }
+
+            let caller = self.env().caller();
+            let mut lock = self.native_locks.get(caller).unwrap_or_default();
+            lock.amount += amount;
+            self.native_locks.insert(caller, &lock);
+            self.checkpoint(CheckpointKind::Lock, caller, lock.amount);
+            self.total_locked += amount;
+
+            Ok(())
+        }
+
+        /// Releases locked native balance once every proposal it voted on has
+        /// finished voting.
+        #[ink(message, selector = 0xAA9EA9DE)]
+        pub fn unlock(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let mut lock = self.native_locks.get(caller).unwrap_or_default();
+
+            if amount == 0 || amount > lock.amount {
+                return Err(GovernorError::InsufficientLockedBalance);
+            }
+            if self.now() < lock.locked_until {
+                return Err(GovernorError::TokensLocked);
+            }
+
+            lock.amount -= amount;
+            if lock.amount == 0 {
+                self.native_locks.remove(caller);
+            } else {
+                self.native_locks.insert(caller, &lock);
+            }
+            self.checkpoint(CheckpointKind::Lock, caller, lock.amount);
+            self.total_locked -= amount;
+
+            self.env()
+                .transfer(caller, amount)
+                .map_err(|_| GovernorError::TransferError)
+        }
+
+        #[ink(message, selector = 0x60F0DCA4)]
+        pub fn get_native_lock(&self, account: AccountId) -> Option<NativeLock> {
+            self.native_locks.get(account)
+        }
+
+        /// Moves `amount` governance tokens from the caller into the governor as
+        /// voting weight. The governor must be approved to spend them.
+        #[ink(message, selector = 0x567C1EAE)]
+        pub fn lock_tokens(&mut self, amount: Balance) -> Result<()> {
+            if !self.uses_source(WeightSource::LockedPsp22) {
+                return Err(GovernorError::UnsupportedWeightSource);
+            }
+            if amount == 0 {
+                return Err(GovernorError::AmountShouldNotBeZero);
+            }
+
+            let caller = self.env().caller();
+            let token = self.config.governance_token;
+            self.ensure_code_intact(token)?;
+            PSP22Ref::transfer_from(&token, caller, self.env().account_id(), amount, Vec::new())
+                .map_err(|_| GovernorError::TokenTransferFailed)?;
+            let mut lock = self.token_locks.get(caller).unwrap_or_default();
+            lock.amount += amount;
+            self.token_locks.insert(caller, &lock);
+            self.checkpoint(CheckpointKind::TokenLock, caller, lock.amount);
+            self.total_tokens_locked += amount;
+
+            Ok(())
+        }
+
+        /// Returns locked governance tokens once every proposal they voted on has
+        /// finished voting.
+        #[ink(message, selector = 0x020CBB8E)]
+        pub fn unlock_tokens(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let mut lock = self.token_locks.get(caller).unwrap_or_default();
+            self.ensure_code_intact(self.config.governance_token)?;
+
+            if amount == 0 || amount > lock.amount {
+                return Err(GovernorError::InsufficientLockedBalance);
+            }
+            if self.now() < lock.locked_until {
+                return Err(GovernorError::TokensLocked);
+            }
+
+            lock.amount -= amount;
+            if lock.amount == 0 {
+                self.token_locks.remove(caller);
+            } else {
+                self.token_locks.insert(caller, &lock);
+            }
+            self.checkpoint(CheckpointKind::TokenLock, caller, lock.amount);
+            self.total_tokens_locked -= amount;
+
+            PSP22Ref::transfer(&self.config.governance_token, caller, amount, Vec::new())
+                .map_err(|_| GovernorError::TokenTransferFailed)
+        }
+
+        /// Adds the transferred value to the treasury and records it with the
+        /// caller as donor. `memo` can reference any off-chain note. Returns the
+        /// donation's id as a receipt.
+        #[ink(message, payable, selector = 0x4AAFA343)]
+        pub fn fund(&mut self, memo: Option<Hash>) -> Result<u32> {
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(GovernorError::AmountShouldNotBeZero);
+            }
+
+            let donor = self.env().caller();
+            let donation_id = self.donation_count;
+            let donation = Donation {
+                donor,
+                amount,
+                memo,
+                funded_at: self.now(),
+            };
+            self.donations.insert(donation_id, &donation);
+            self.donation_count += 1;
+            let total = self.donor_totals.get(donor).unwrap_or(0);
+            self.donor_totals
+                .insert(donor, &total.saturating_add(amount));
+            self.total_funded = self.total_funded.saturating_add(amount);
+            self.env().emit_event(TreasuryFunded {
+                donation_id,
+                donor,
+                amount,
+                memo,
+            });
+
+            Ok(donation_id)
+        }
+
+        #[ink(message, selector = 0xE3885FE3)]
+        pub fn get_donation(&self, donation_id: u32) -> Option<Donation> {
+            self.donations.get(donation_id)
+        }
+
+        /// Returns what `donor` contributed through `fund` and what all donors
+        /// contributed.
+        #[ink(message, selector = 0x855549F1)]
+        pub fn get_donor_total(&self, donor: AccountId) -> (Balance, Balance) {
+            (self.donor_totals.get(donor).unwrap_or(0), self.total_funded)
+        }
+
+        /// Total of `token` received through transfers under `earmark`, or for
+        /// the general treasury without one.
+        #[ink(message, selector = 0x54758867)]
+        pub fn get_token_deposits(&self, token: AccountId, earmark: Option<String>) -> Balance {
+            self.token_deposits
+                .get((token, earmark.unwrap_or_default()))
+                .unwrap_or(0)
+        }
+
+        #[ink(message, selector = 0x1209982A)]
+        pub fn get_token_lock(&self, account: AccountId) -> Option<NativeLock> {
+            self.token_locks.get(account)
+        }
+
+        /// Hands the caller's current token balance to `delegatee`, replacing any
+        /// earlier delegation. Calling it again refreshes the delegated weight.
+        /// Holders delegate to themselves to vote with their own balance: token
+        /// weight only counts through delegation checkpoints, so self-delegation
+        /// is allowed. Moving the tokens later doesn't lower the delegation until
+        /// `sync_delegation` caps it.
+        #[ink(message, selector = 0xC59654FE)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<()> {
+            self.set_delegation(self.env().caller(), delegatee)
+        }
+
+        /// Delegates `delegator`'s weight to `delegatee` through a relayer, for
+        /// custodial and hardware wallets. The signed payload is `delegatee`
+        /// (see the `signature` module).
+        #[ink(message, selector = 0x7479B369)]
+        pub fn delegate_by_sig(
+            &mut self,
+            delegator: AccountId,
+            delegatee: AccountId,
+            nonce: u64,
+            expiry: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            let signed = Signed {
+                operation: Operation::Delegate,
+                nonce,
+                deadline: expiry,
+                signature,
+            };
+            self.use_signature(delegator, &delegatee, signed)?;
+            self.set_delegation(delegator, delegatee)
+        }
+
+        #[ink(message, selector = 0x2456DE34)]
+        pub fn undelegate(&mut self) -> Result<()> {
+            let delegator = self.env().caller();
+            if !self.delegations.contains(delegator) {
+                return Err(GovernorError::NotDelegating);
+            }
+
+            self.remove_delegation(delegator);
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                delegatee: None,
+                weight: 0,
+            });
+
+            Ok(())
+        }
+
+        /// Caps a delegation at what the delegator holds now, taking the excess
+        /// from its delegatee. Delegations copy the balance once, so tokens moved
+        /// afterwards would otherwise count for the old delegatee and again for
+        /// their new holder. Anyone can call it, typically before a snapshot.
+        #[ink(message, selector = 0xC2A59EDF)]
+        pub fn sync_delegation(&mut self, delegator: AccountId) -> Result<()> {
+            let mut delegation = self
+                .delegations
+                .get(delegator)
+                .ok_or(GovernorError::NotDelegating)?;
+            self.ensure_code_intact(self.config.governance_token)?;
+            let held = self.token_weight(self.config.governance_token, delegator);
+            if held >= delegation.weight {
+                return Ok(());
+            }
+
+            let received = self.get_delegated_weight(delegation.delegatee);
+            self.checkpoint(
+                CheckpointKind::Delegated,
+                delegation.delegatee,
+                received.saturating_sub(delegation.weight - held),
+            );
+            delegation.weight = held;
+            delegation.affirmed_weight = delegation.affirmed_weight.min(held);
+            self.delegations.insert(delegator, &delegation);
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                delegatee: Some(delegation.delegatee),
+                weight: held,
+            });
+
+            Ok(())
+        }
+
+        /// Applies the decay a delegation has accrued since it was last affirmed,
+        /// taking the lost weight from its delegatee. Anyone can call it.
+        #[ink(message, selector = 0x12AA65A4)]
+        pub fn decay_delegation(&mut self, delegator: AccountId) -> Result<()> {
+            let mut delegation = self
+                .delegations
+                .get(delegator)
+                .ok_or(GovernorError::NotDelegating)?;
+            let elapsed = self.now().saturating_sub(delegation.affirmed_at);
+            let weight = weight::decayed(
+                delegation.affirmed_weight,
+                elapsed,
+                self.config.delegation_decay_period,
+            );
+            if weight >= delegation.weight {
+                return Ok(());
+            }
+
+            let received = self.get_delegated_weight(delegation.delegatee);
+            self.checkpoint(
+                CheckpointKind::Delegated,
+                delegation.delegatee,
+                received.saturating_sub(delegation.weight - weight),
+            );
+            delegation.weight = weight;
+            self.delegations.insert(delegator, &delegation);
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                delegatee: Some(delegation.delegatee),
+                weight,
+            });
+
+            Ok(())
+        }
+
+        /// Records the governance tokens backing `account`'s registered LP
+        /// positions. They count towards its `Psp22` weight as of later
+        /// snapshots, for as long as the account still holds them when it votes.
+        /// Callable by anyone.
+        #[ink(message, selector = 0x6FCDD0AD)]
+        pub fn checkpoint_lp_weight(&mut self, account: AccountId) -> Result<()> {
+            if self.lp_pools.is_empty() {
+                return Err(GovernorError::UnsupportedWeightSource);
+            }
+
+            let weight = self.lp_weight(account);
+            self.checkpoint(CheckpointKind::Lp, account, weight);
+            Ok(())
+        }
+
+        #[ink(message, selector = 0x3D11CFC8)]
+        pub fn get_lp_pools(&self) -> Vec<LpPool> {
+            self.lp_pools.clone()
+        }
+
+        #[ink(message, selector = 0x0DB9C910)]
+        pub fn get_delegation(&self, delegator: AccountId) -> Option<Delegation> {
+            self.delegations.get(delegator)
+        }
+
+        /// Delegated weight `account` held at `timestamp`. Checkpoints older than
+        /// `CHECKPOINT_RETENTION` are pruned, so earlier times may read the last
+        /// weight before the pruning horizon instead.
+        #[ink(message, selector = 0x7995D5FC)]
+        pub fn get_past_votes(&self, account: AccountId, timestamp: Timestamp) -> Balance {
+            let checkpoints = self.delegated_checkpoints.get(account).unwrap_or_default();
+            weight::weight_before(&checkpoints, timestamp.saturating_add(1))
+        }
+
+        #[ink(message, selector = 0x7C030CBB)]
+        pub fn get_delegated_weight(&self, delegate: AccountId) -> Balance {
+            weight::latest(&self.delegated_checkpoints.get(delegate).unwrap_or_default())
+        }
+
+        /// Weight `account` can vote with on a proposal: what it held just before
+        /// the proposal's snapshot, its `vote_start`.
+        #[ink(message, selector = 0x04AD5000)]
+        pub fn weight_of_at(&self, account: AccountId, proposal_id: ProposalId) -> Result<Balance> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            let weights = self.strategy_weights(account, proposal_id, &proposal);
+            Ok(weights
+                .iter()
+                .fold(0, |total: Balance, w| total.saturating_add(*w)))
+        }
+
+        /// Registers the caller as a delegate, or updates their statement, where
+        /// `statement` is the hash of their published platform.
+        #[ink(message, selector = 0xE48BF89A)]
+        pub fn set_delegate_statement(&mut self, statement: Hash) -> Result<()> {
+            let delegate = self.env().caller();
+            if !self.delegate_statements.contains(delegate) {
+                self.delegate_list.insert(self.delegate_count, &delegate);
+                self.delegate_count += 1;
+            }
+            self.delegate_statements.insert(delegate, &statement);
+
+            self.env().emit_event(DelegateStatementSet {
+                delegate,
+                statement,
+            });
+
+            Ok(())
+        }
+
+        /// Returns up to `n` accounts with the most delegated weight, heaviest
+        /// first, from a leaderboard of `TOP_DELEGATES_CAPACITY` entries.
+        #[ink(message, selector = 0x068A6D44)]
+        pub fn top_delegates(&self, n: u32) -> Vec<(AccountId, Balance)> {
+            let mut top = self.top_delegates.get().unwrap_or_default();
+            top.truncate(n as usize);
+            top
+        }
+
+        #[ink(message, selector = 0xCE648AC1)]
+        pub fn get_delegate_statement(&self, delegate: AccountId) -> Option<Hash> {
+            self.delegate_statements.get(delegate)
+        }
+
+        /// Returns `(delegate, statement, delegated weight)` for registered
+        /// delegates in registration order, at most `MAX_PAGE_SIZE` per call.
+        #[ink(message, selector = 0xE0464546)]
+        pub fn get_delegates(&self, offset: u32, limit: u32) -> Vec<(AccountId, Hash, Balance)> {
+            let end = offset
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.delegate_count);
+
+            (offset..end)
+                .filter_map(|index| {
+                    let delegate = self.delegate_list.get(index)?;
+                    let statement = self.delegate_statements.get(delegate)?;
+                    Some((delegate, statement, self.get_delegated_weight(delegate)))
+                })
+                .collect()
+        }
+
+        /// Executes a passed proposal. If only the treasury balance is short, the
+        /// failure is recorded with an `ExecutionFailed` event instead of
+        /// reverting; see `execution_attempts`.
+        #[ink(message, selector = 0xD00EC894)]
+        pub fn execute(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.activate_queued();
+            let (proposal, mut status, outcome) = match self.executable_proposal(proposal_id) {
+                Ok(executable) => executable,
+                Err(error @ GovernorError::InsufficientTreasuryBalance { .. }) => {
+                    return self.record_failed_execution(proposal_id, error)
+                }
+                Err(error) => return Err(error),
+            };
+            status.flags |= STATUS_FINALIZED | STATUS_EXECUTED;
+            self.proposal_status.insert(proposal_id, &status);
+            self.store_outcome(proposal_id, &proposal, &outcome)?;
+            self.update_timeline(proposal_id, |timeline, now| timeline.executed = Some(now));
+
+            if proposal.amount > 0 {
+                self.escrow_payout(proposal_id, proposal.to, proposal.amount);
+            }
+            for action in self
+                .proposal_actions
+                .get(proposal_id)
+                .unwrap_or_default()
+                .iter()
+            {
+                self.apply_action(proposal_id, action)?;
+            }
+
+            let mut record = self
+                .proposer_records
+                .get(proposal.proposer)
+                .unwrap_or_default();
+            record.executed += 1;
+            self.proposer_records.insert(proposal.proposer, &record);
+
+            self.run_hook(hooks::ON_EXECUTE, (proposal_id, self.env().caller()))?;
+            self.record_change(ChangeKind::ProposalExecuted, proposal_id);
+            self.env().emit_event(ProposalExecuted {
+                proposal_id,
+                executor: self.env().caller(),
+            });
+
+            Ok(())
+        }
+
+        /// Cancels a proposal. The proposer can cancel while voting is open, a
+        /// guardian at any time before execution. The bond goes to the treasury.
+        #[ink(message, selector = 0x9796E9A7)]
+        pub fn cancel(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.activate_queued();
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            let canceler = self.env().caller();
+            let pending = self.pending_durations.contains(proposal_id);
+            if canceler != proposal.proposer {
+                self.ensure_role(GUARDIAN_ROLE)?;
+            } else if !pending && self.now() >= proposal.vote_end {
+                return Err(GovernorError::VotePeriodEnded {
+                    ended_at: proposal.vote_end,
+                    now: self.now(),
+                });
+            }
+
+            let mut status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+            if status.has(STATUS_EXECUTED) {
+                return Err(GovernorError::ProposalAlreadyExecuted);
+            }
+
+            status.flags |= STATUS_CANCELED;
+            self.proposal_status.insert(proposal_id, &status);
+            self.pending_durations.remove(proposal_id);
+            self.drafts.remove(proposal_id);
+            self.active_proposals.retain(|id| *id != proposal_id);
+            self.close_proposal(proposal_id);
+            if self.council_election == Some(proposal_id) {
+                self.council_election = None;
+            }
+            if self.pending_emergency == Some(proposal_id) {
+                self.pending_emergency = None;
+            }
+            if let Some(paused_id) = self.pause_reviews.get(proposal_id) {
+                self.settle_pause(paused_id, false);
+            }
+            if let Some(escrow) = self.escrow_disputes.get(proposal_id) {
+                self.escrow_disputes.remove(proposal_id);
+                self.do_clawback(escrow)?;
+            }
+            self.update_timeline(proposal_id, |timeline, now| timeline.canceled = Some(now));
+            if let Some(bond) = self.bonds.get(proposal_id) {
+                self.bonds.remove(proposal_id);
+                self.total_bonded -= bond;
+            }
+
+            self.run_hook(hooks::ON_CANCEL, (proposal_id, canceler))?;
+            self.record_change(ChangeKind::ProposalCanceled, proposal_id);
+            self.env().emit_event(ProposalCanceled {
+                proposal_id,
+                canceler,
+            });
+
+            Ok(())
+        }
+
+        /// Opens a draft proposal for voting once its discussion period is over,
+        /// or queues it if the active proposal slots are full. Proposer only.
+        #[ink(message, selector = 0x5CCBFB51)]
+        pub fn activate(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.activate_queued();
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            if self.env().caller() != proposal.proposer {
+                return Err(GovernorError::NotProposer);
+            }
+            let discussion_end = self
+                .drafts
+                .get(proposal_id)
+                .ok_or(GovernorError::NotDraft)?;
+            if self.now() < discussion_end {
+                return Err(GovernorError::DiscussionPeriodNotOver);
+            }
+            let sponsors = self.sponsors.get(proposal_id).unwrap_or_default();
+            if sponsors.len() < self.config.required_sponsors as usize {
+                return Err(GovernorError::SponsorsMissing);
+            }
+            let duration = self
+                .pending_durations
+                .get(proposal_id)
+                .ok_or(GovernorError::NotDraft)?;
+
+            self.drafts.remove(proposal_id);
+            if self.active_proposals.len() < self.config.max_active_proposals as usize {
+                self.pending_durations.remove(proposal_id);
+                self.open_proposal(proposal_id, duration);
+            } else {
+                self.enqueue(proposal_id, duration);
+            }
+
+            Ok(())
+        }
+
+        /// Endorses a draft so it can be activated once it has
+        /// `required_sponsors` sponsors. Sponsors need `min_sponsor_weight` of
+        /// weight from before the current block, can't be the proposer, and
+        /// lose the right to sponsor after `MAX_SPONSOR_STRIKES` strikes.
+        #[ink(message, selector = 0xDDE27015)]
+        pub fn sponsor(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            if !self.drafts.contains(proposal_id) {
+                return Err(GovernorError::NotDraft);
+            }
+
+            let sponsor = self.env().caller();
+            let mut sponsors = self.sponsors.get(proposal_id).unwrap_or_default();
+            if sponsor == proposal.proposer || sponsors.contains(&sponsor) {
+                return Err(GovernorError::AlreadySponsored);
+            }
+            if sponsors.len() >= self.config.required_sponsors as usize {
+                return Err(GovernorError::InvalidParameter);
+            }
+            self.ensure_eligible(sponsor)?;
+            if self.get_sponsor_strikes(sponsor) >= MAX_SPONSOR_STRIKES {
+                return Err(GovernorError::NotEligible);
+            }
+            let weight = self.weight_at(self.config.weight_source, sponsor, self.now());
+            if weight == 0 || weight < self.config.min_sponsor_weight {
+                return Err(GovernorError::VoteWeightBelowMinimum {
+                    have: weight,
+                    need: self.config.min_sponsor_weight,
+                });
+            }
+
+            sponsors.push(sponsor);
+            self.sponsors.insert(proposal_id, &sponsors);
+            self.env().emit_event(ProposalSponsored {
+                proposal_id,
+                sponsor,
+                sponsors: sponsors.len() as u32,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message, selector = 0xE5F74BA0)]
+        pub fn get_sponsors(&self, proposal_id: ProposalId) -> Vec<AccountId> {
+            self.sponsors.get(proposal_id).unwrap_or_default()
+        }
+
+        /// Number of sponsored proposals that failed with no more than
+        /// `SPONSOR_STRIKE_THRESHOLD` approval, since governance last cleared the
+        /// sponsor's strikes with `ClearSponsorStrikes`.
+        #[ink(message, selector = 0xA612755B)]
+        pub fn get_sponsor_strikes(&self, sponsor: AccountId) -> u32 {
+            self.sponsor_strikes.get(sponsor).unwrap_or(0)
+        }
+
+        /// Replaces the `Hidden` placeholder of a proposal with the actions it
+        /// stands for, once voting has ended. Only the proposer can reveal, and
+        /// `actions` and `salt` must hash to the placeholder's hash. Revealed
+        /// actions can't change how the proposal was voted on or paid out.
+        #[ink(message, selector = 0x2CBB3D2B)]
+        pub fn reveal_actions(
+            &mut self,
+            proposal_id: ProposalId,
+            actions: Vec<ProposalAction>,
+            salt: [u8; 32],
+        ) -> Result<()> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            if self.env().caller() != proposal.proposer {
+                return Err(GovernorError::NotProposer);
+            }
+            let now = self.now();
+            if self.pending_durations.contains(proposal_id) || now < proposal.vote_end {
+                return Err(GovernorError::VotePeriodNotEnded {
+                    ends_at: proposal.vote_end,
+                    now,
+                });
+            }
+            let mut stored = self.proposal_actions.get(proposal_id).unwrap_or_default();
+            let index = stored
+                .iter()
+                .position(|action| matches!(action, ProposalAction::Hidden(_)))
+                .ok_or(GovernorError::InvalidParameter)?;
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(&actions, salt), &mut output);
+            if stored[index] != ProposalAction::Hidden(Hash::from(output)) {
+                return Err(GovernorError::PayloadMismatch);
+            }
+            for action in actions.iter() {
+                let revealable = !matches!(
+                    action,
+                    ProposalAction::Hidden(_)
+                        | ProposalAction::WholeTokenAmounts
+                        | ProposalAction::Options(_)
+                        | ProposalAction::Election { .. }
+                        | ProposalAction::TreasuryShare { .. }
+                        | ProposalAction::UsdPayout { .. }
+                        | ProposalAction::Arbitrated(_)
+                        | ProposalAction::LiftPause(_)
+                        | ProposalAction::Shutdown { .. }
+                        | ProposalAction::MigrateTo(_)
+                        | ProposalAction::RotateGuardian { .. }
+                );
+                if !revealable {
+                    return Err(GovernorError::InvalidParameter);
+                }
+                self.validate_action(action)?;
+            }
+            if stored.len() - 1 + actions.len() > self.config.max_actions as usize {
+                return Err(GovernorError::PayloadTooLarge);
+            }
+
+            let count = actions.len() as u32;
+            stored.splice(index..index + 1, actions);
+            self.proposal_actions.insert(proposal_id, &stored);
+            self.env()
+                .emit_event(ActionsRevealed { proposal_id, count });
+
+            Ok(())
+        }
+
+        /// Attaches the hash of a translation of the proposal's title and
+        /// description, e.g. an IPFS document, under `locale`. Only the proposer
+        /// can add translations, one per locale, before voting ends.
+        #[ink(message, selector = 0xAEA3FFCE)]
+        pub fn add_translation(
+            &mut self,
+            proposal_id: ProposalId,
+            locale: String,
+            content_hash: Hash,
+        ) -> Result<()> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            if self.env().caller() != proposal.proposer {
+                return Err(GovernorError::NotProposer);
+            }
+            if !self.pending_durations.contains(proposal_id) && self.now() >= proposal.vote_end {
+                return Err(GovernorError::VotePeriodEnded {
+                    ended_at: proposal.vote_end,
+                    now: self.now(),
+                });
+            }
+            if locale.is_empty() || locale.len() > MAX_LOCALE_LENGTH {
+                return Err(GovernorError::InvalidParameter);
+            }
+
+            let mut translations = self.translations.get(proposal_id).unwrap_or_default();
+            if translations.iter().any(|(existing, _)| *existing == locale) {
+                return Err(GovernorError::TranslationExists);
+            }
+            if translations.len() >= MAX_TRANSLATIONS as usize {
+                return Err(GovernorError::TooManyTranslations);
+            }
+            translations.push((locale.clone(), content_hash));
+            self.translations.insert(proposal_id, &translations);
+            self.env().emit_event(TranslationAdded {
+                proposal_id,
+                locale,
+                content_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Pays up to `emergency_cap` to `to` right away, for incident response.
+        /// Callable by guardians and council members with `emergency_bond`
+        /// attached. A ratification proposal by the caller opens at the same
+        /// time, and the bond is slashed if it fails. Only one emergency spend
+        /// can await ratification at a time.
+        #[ink(message, payable, selector = 0xAA774649)]
+        pub fn emergency_spend(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            title: String,
+        ) -> Result<ProposalId> {
+            self.ensure_not_shut_down()?;
+            let spender = self.env().caller();
+            if !self.has_role(GUARDIAN_ROLE, spender) && !self.has_role(COUNCIL_ROLE, spender) {
+                return Err(GovernorError::MissingRole);
+            }
+            if !self.guardian_powers_active() {
+                return Err(GovernorError::GuardianPowersExpired);
+            }
+            if self.config.emergency_cap == 0 {
+                return Err(GovernorError::EmergencyDisabled);
+            }
+            if amount == 0 {
+                return Err(GovernorError::AmountShouldNotBeZero);
+            }
+            if amount > self.config.emergency_cap {
+                return Err(GovernorError::EmergencyCapExceeded);
+            }
+            if self.pending_emergency.is_some() {
+                return Err(GovernorError::EmergencyPending);
+            }
+            if title.len() > self.config.max_payload_size as usize {
+                return Err(GovernorError::PayloadTooLarge);
+            }
+            let bond = self.env().transferred_value();
+            if bond != self.config.emergency_bond {
+                return Err(GovernorError::IncorrectBond);
+            }
+            self.ensure_treasury_covers(amount)?;
+
+            self.activate_queued();
+            let id = self.create_system_proposal(
+                spender,
+                EMERGENCY_CATEGORY,
+                title,
+                self.config.max_vote_duration,
+                self.config.approval_threshold,
+                Vec::new(),
+            );
+            if bond > 0 {
+                self.bonds.insert(id, &bond);
+                self.total_bonded += bond;
+            }
+            let spend = EmergencySpend {
+                spender,
+                to,
+                amount,
+                spent_at: self.now(),
+            };
+            self.emergency_spends.insert(id, &spend);
+            self.pending_emergency = Some(id);
+
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| GovernorError::TransferError)?;
+            self.env().emit_event(EmergencySpent {
+                proposal_id: id,
+                spender,
+                to,
+                amount,
+            });
+
+            Ok(id)
+        }
+
+        #[ink(message, selector = 0x8F08D0B4)]
+        pub fn get_emergency_spend(&self, proposal_id: ProposalId) -> Option<EmergencySpend> {
+            self.emergency_spends.get(proposal_id)
+        }
+
+        /// Pays out the escrowed amount of an executed proposal to its recipient.
+        #[ink(message, selector = 0xB388803F)]
+        pub fn claim(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            if self.env().caller() != escrow.recipient {
+                return Err(GovernorError::NotRecipient);
+            }
+
+            self.pay_escrow(proposal_id, escrow)
+        }
+
+        /// Registers the caller as a watchdog, bonding exactly `watchdog_bond`.
+        #[ink(message, payable, selector = 0xD494BA09)]
+        pub fn register_watchdog(&mut self) -> Result<()> {
+            let watchdog = self.env().caller();
+            if self.config.watchdog_bond == 0 {
+                return Err(GovernorError::WatchdogsDisabled);
+            }
+            if self.watchdogs.contains(watchdog) {
+                return Err(GovernorError::AlreadyWatchdog);
+            }
+            let bond = self.env().transferred_value();
+            if bond != self.config.watchdog_bond {
+                return Err(GovernorError::IncorrectBond);
+            }
+
+            self.watchdogs.insert(
+                watchdog,
+                &Watchdog {
+                    bond,
+                    pausing: None,
+                },
+            );
+            self.total_bonded += bond;
+            self.env().emit_event(WatchdogRegistered { watchdog, bond });
+
+            Ok(())
+        }
+
+        /// Stops being a watchdog and takes the bond back. Not possible while a
+        /// pause of the caller is under review.
+        #[ink(message, selector = 0x80CE81E2)]
+        pub fn unregister_watchdog(&mut self) -> Result<()> {
+            let account = self.env().caller();
+            let watchdog = self
+                .watchdogs
+                .get(account)
+                .ok_or(GovernorError::NotWatchdog)?;
+            if watchdog.pausing.is_some() {
+                return Err(GovernorError::AlreadyPaused);
+            }
+
+            self.watchdogs.remove(account);
+            self.total_bonded -= watchdog.bond;
+            self.env()
+                .transfer(account, watchdog.bond)
+                .map_err(|_| GovernorError::TransferError)?;
+
+            Ok(())
+        }
+
+        /// Blocks the execution of a finalized proposal that passed, and opens a
+        /// `LiftPause` proposal in the `WATCHDOG` category reviewing the pause.
+        /// The governor is the review's proposer, so the watchdog can't cancel
+        /// it. Each proposal can be paused once, and each watchdog holds one
+        /// pause under review at a time.
+        #[ink(message, selector = 0x30307A6A)]
+        pub fn watchdog_pause(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.ensure_not_shut_down()?;
+            let account = self.env().caller();
+            let mut watchdog = self
+                .watchdogs
+                .get(account)
+                .ok_or(GovernorError::NotWatchdog)?;
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            let now = self.now();
+            if self.pending_durations.contains(proposal_id) || now < proposal.vote_end {
+                return Err(GovernorError::VotePeriodNotEnded {
+                    ends_at: proposal.vote_end,
+                    now,
+                });
+            }
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+            if status.has(STATUS_EXECUTED) {
+                return Err(GovernorError::ProposalAlreadyExecuted);
+            }
+            if !matches!(self.outcomes.get(proposal_id), Some(outcome) if outcome.passed) {
+                return Err(GovernorError::ProposalNotPassed);
+            }
+            if watchdog.pausing.is_some() || self.pauses.contains(proposal_id) {
+                return Err(GovernorError::AlreadyPaused);
+            }
+
+            self.activate_queued();
+            let review_id = self.create_system_proposal(
+                self.env().account_id(),
+                WATCHDOG_CATEGORY,
+                String::from("Watchdog pause review"),
+                self.config.max_vote_duration,
+                self.config.approval_threshold,
+                ink::prelude::vec![ProposalAction::LiftPause(proposal_id)],
+            );
+            let pause = Pause {
+                watchdog: account,
+                review: review_id,
+            };
+            self.pauses.insert(proposal_id, &pause);
+            self.pause_reviews.insert(review_id, &proposal_id);
+            watchdog.pausing = Some(proposal_id);
+            self.watchdogs.insert(account, &watchdog);
+            self.env().emit_event(ProposalPausedByWatchdog {
+                proposal_id,
+                watchdog: account,
+                review_id,
+            });
+
+            Ok(())
+        }
+
+        /// Lifts a pause whose review passed but expired unexecuted, slashing the
+        /// watchdog as if the review had been executed. Callable by anyone.
+        #[ink(message, selector = 0x16CA73C2)]
+        pub fn settle_expired_review(&mut self, review_id: ProposalId) -> Result<()> {
+            let paused_id = self
+                .pause_reviews
+                .get(review_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            if self.state(review_id) != Some(ProposalState::Expired) {
+                return Err(GovernorError::ReviewPending);
+            }
+
+            self.settle_pause(paused_id, false);
+            Ok(())
+        }
+
+        /// Runtime referendum index recorded by an executed proposal's
+        /// `SubmitReferendum` or `LinkReferendum` action.
+        #[ink(message, selector = 0xF6A4E9A6)]
+        pub fn get_referendum(&self, proposal_id: ProposalId) -> Option<u32> {
+            self.referenda.get(proposal_id)
+        }
+
+        /// Code hash `contract` is pinned to by `PinCode` or as the governance
+        /// token, which is pinned at deployment and on migration.
+        #[ink(message, selector = 0x650718BF)]
+        pub fn get_pinned_code(&self, contract: AccountId) -> Option<Hash> {
+            self.pinned_code.get(contract)
+        }
+
+        /// Checks a pinned dependency or strategy adapter against its pinned code
+        /// hash, emitting `DependencyCodeChanged` if it changed. Callable by
+        /// anyone, e.g. monitoring bots; functionality relying on the contract
+        /// is already stopped whether or not this is called.
+        #[ink(message, selector = 0x5ABA1904)]
+        pub fn check_code(&self, contract: AccountId) -> bool {
+            let expected = match self.expected_code(contract) {
+                Some(expected) => expected,
+                None => return true,
+            };
+            let found = self.env().code_hash(&contract).ok();
+            if found == Some(expected) {
+                return true;
+            }
+
+            self.env().emit_event(DependencyCodeChanged {
+                contract,
+                expected,
+                found,
+            });
+            false
+        }
+
+        /// Code hash a registered strategy adapter is pinned to.
+        #[ink(message, selector = 0x3DF6BB95)]
+        pub fn get_strategy_adapter(&self, adapter: AccountId) -> Option<Hash> {
+            self.strategy_adapters.get(adapter)
+        }
+
+        #[ink(message, selector = 0x8B626680)]
+        pub fn is_voter_code_allowed(&self, code_hash: Hash) -> bool {
+            self.allowed_voter_code.contains(code_hash)
+        }
+
+        #[ink(message, selector = 0x0D6D7EA2)]
+        pub fn get_watchdog(&self, account: AccountId) -> Option<Watchdog> {
+            self.watchdogs.get(account)
+        }
+
+        #[ink(message, selector = 0xCB9FAACB)]
+        pub fn get_pause(&self, proposal_id: ProposalId) -> Option<Pause> {
+            self.pauses.get(proposal_id)
+        }
+
+        /// Starts the objection window of a two-phase payout. Callable by anyone.
+        #[ink(message, selector = 0xCB3BF9B9)]
+        pub fn approve_disbursement(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let mut escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            if !escrow.two_phase || escrow.disbursable_at.is_some() {
+                return Err(GovernorError::InvalidParameter);
+            }
+            if escrow.objected {
+                return Err(GovernorError::DisbursementObjected);
+            }
+            if escrow.arbiter.is_some() && !escrow.attested {
+                return Err(GovernorError::EscrowNotAttested);
+            }
+
+            let disbursable_at = self.now() + self.config.objection_window;
+            escrow.disbursable_at = Some(disbursable_at);
+            self.escrows.insert(proposal_id, &escrow);
+            self.env().emit_event(DisbursementApproved {
+                proposal_id,
+                amount: escrow.amount,
+                disbursable_at,
+            });
+
+            Ok(())
+        }
+
+        /// Pays out an escrowed payout to its recipient, like `claim`. Callable
+        /// by anyone, so two-phase payouts complete without the recipient.
+        #[ink(message, selector = 0x28217C4A)]
+        pub fn disburse(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            self.pay_escrow(proposal_id, escrow)
+        }
+
+        /// Holds a two-phase payout during its objection window and opens a
+        /// `ResolveEscrow` proposal on releasing it anyway. The payout is clawed
+        /// back if that proposal is defeated or canceled. Guardian only.
+        #[ink(message, selector = 0x1E560DEE)]
+        pub fn object_to_disbursement(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.ensure_role(GUARDIAN_ROLE)?;
+            let mut escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            match escrow.disbursable_at {
+                Some(disbursable_at) if self.now() < disbursable_at => {}
+                _ => return Err(GovernorError::DisbursementNotApproved),
+            }
+            if escrow.objected {
+                return Err(GovernorError::DisbursementObjected);
+            }
+            if escrow.dispute.is_some() {
+                return Err(GovernorError::EscrowDisputed);
+            }
+
+            self.ensure_not_shut_down()?;
+            self.activate_queued();
+            let action = ProposalAction::ResolveEscrow {
+                escrow: proposal_id,
+                release: true,
+            };
+            let resolution_id = self.create_system_proposal(
+                self.env().account_id(),
+                DISPUTE_CATEGORY,
+                String::from("Disbursement objection"),
+                self.config.max_vote_duration,
+                self.config.approval_threshold,
+                ink::prelude::vec![action],
+            );
+            escrow.objected = true;
+            escrow.dispute = Some(resolution_id);
+            self.escrows.insert(proposal_id, &escrow);
+            self.escrow_disputes.insert(resolution_id, &proposal_id);
+            self.env().emit_event(DisbursementObjected {
+                proposal_id,
+                guardian: self.env().caller(),
+                resolution_id,
+            });
+
+            Ok(())
+        }
+
+        fn pay_escrow(&mut self, proposal_id: ProposalId, escrow: Escrow) -> Result<()> {
+            if escrow.arbiter.is_some() && !escrow.attested {
+                return Err(GovernorError::EscrowNotAttested);
+            }
+            if escrow.objected {
+                return Err(GovernorError::DisbursementObjected);
+            }
+            if escrow.two_phase {
+                match escrow.disbursable_at {
+                    None => return Err(GovernorError::DisbursementNotApproved),
+                    Some(disbursable_at) if self.now() < disbursable_at => {
+                        return Err(GovernorError::ObjectionWindowOpen)
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            self.escrows.remove(proposal_id);
+            self.total_escrowed -= escrow.amount;
+            self.env()
+                .transfer(escrow.recipient, escrow.amount)
+                .map_err(|_| GovernorError::TransferError)?;
+
+            self.record_change(ChangeKind::PayoutClaimed, proposal_id);
+            self.env().emit_event(PayoutClaimed {
+                proposal_id,
+                recipient: escrow.recipient,
+                amount: escrow.amount,
+            });
+
+            Ok(())
+        }
+
+        /// Pays `amount` of an airdrop to the caller, given the merkle `proof` of
+        /// the leaf `(airdrop_id, caller, amount)`. Each account claims once.
+        #[ink(message, selector = 0x0BA35542)]
+        pub fn claim_airdrop(
+            &mut self,
+            airdrop_id: u32,
+            proof: Vec<Hash>,
+            amount: Balance,
+        ) -> Result<()> {
+            let mut airdrop = self
+                .airdrops
+                .get(airdrop_id)
+                .ok_or(GovernorError::AirdropNotFound)?;
+
+            let account = self.env().caller();
+            if self.airdrop_claims.contains((airdrop_id, account)) {
+                return Err(GovernorError::AlreadyClaimed);
+            }
+            let proof: Vec<merkle::Node> = proof.into_iter().map(Into::into).collect();
+            let leaf = merkle::leaf(&(airdrop_id, account, amount));
+            if !merkle::verify(&proof, &airdrop.root.into(), leaf) {
+                return Err(GovernorError::InvalidProof);
+            }
+            if amount > airdrop.budget - airdrop.claimed {
+                return Err(GovernorError::AirdropExhausted);
+            }
+
+            if let Some(token) = airdrop.token {
+                self.ensure_code_intact(token)?;
+            }
+
+            airdrop.claimed += amount;
+            self.airdrops.insert(airdrop_id, &airdrop);
+            self.airdrop_claims.insert((airdrop_id, account), &());
+            match airdrop.token {
+                Some(token) => {
+                    self.release_airdrop_tokens(token, amount);
+                    PSP22Ref::transfer(&token, account, amount, Vec::new())
+                        .map_err(|_| GovernorError::TokenTransferFailed)?
+                }
+                None => {
+                    self.total_airdrop_reserved -= amount;
+                    self.env()
+                        .transfer(account, amount)
+                        .map_err(|_| GovernorError::TransferError)?;
+                }
+            }
+
+            self.env().emit_event(AirdropClaimed {
+                airdrop_id,
+                account,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message, selector = 0x63492B4C)]
+        pub fn get_airdrop(&self, airdrop_id: u32) -> Option<Airdrop> {
+            self.airdrops.get(airdrop_id)
+        }
+
+        #[ink(message, selector = 0x1FD727F7)]
+        pub fn has_claimed_airdrop(&self, airdrop_id: u32, account: AccountId) -> bool {
+            self.airdrop_claims.contains((airdrop_id, account))
+        }
+
+        #[ink(message, selector = 0x5D715835)]
+        pub fn get_escrow(&self, proposal_id: ProposalId) -> Option<Escrow> {
+            self.escrows.get(proposal_id)
+        }
+
+        /// The arbiter's verdict on an arbitrated payout. Attesting completion
+        /// lets the recipient claim it; disputing it opens a `ResolveEscrow`
+        /// proposal deciding whether to release the payout anyway. The payout is
+        /// clawed back if that proposal is defeated or canceled.
+        #[ink(message, selector = 0xDFADFE37)]
+        pub fn attest(&mut self, proposal_id: ProposalId, completed: bool) -> Result<()> {
+            let mut escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            let arbiter = self.env().caller();
+            if escrow.arbiter != Some(arbiter) {
+                return Err(GovernorError::NotArbiter);
+            }
+            if escrow.attested || escrow.dispute.is_some() {
+                return Err(GovernorError::EscrowDisputed);
+            }
+
+            if completed {
+                escrow.attested = true;
+                self.escrows.insert(proposal_id, &escrow);
+                self.env().emit_event(EscrowAttested {
+                    proposal_id,
+                    arbiter,
+                });
+                return Ok(());
+            }
+
+            self.ensure_not_shut_down()?;
+            self.activate_queued();
+            let action = ProposalAction::ResolveEscrow {
+                escrow: proposal_id,
+                release: true,
+            };
+            let resolution_id = self.create_system_proposal(
+                self.env().account_id(),
+                DISPUTE_CATEGORY,
+                String::from("Escrow dispute"),
+                self.config.max_vote_duration,
+                self.config.approval_threshold,
+                ink::prelude::vec![action],
+            );
+            escrow.dispute = Some(resolution_id);
+            self.escrows.insert(proposal_id, &escrow);
+            self.escrow_disputes.insert(resolution_id, &proposal_id);
+            self.env().emit_event(EscrowDisputed {
+                proposal_id,
+                resolution_id,
+            });
+
+            Ok(())
+        }
+
+        /// Returns a payout nobody claimed within `claim_period` to the treasury.
+        /// Callable by anyone, but not while the payout waits on its arbiter or
+        /// a dispute vote; a dispute that fails claws the payout back itself.
+        #[ink(message, selector = 0x7DB549F9)]
+        pub fn reclaim_expired(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            let claim_period = self.config.claim_period;
+            if claim_period == 0 || self.now() < escrow.created_at + claim_period {
+                return Err(GovernorError::ClaimPeriodNotOver);
+            }
+            // A dispute settles the escrow itself; one whose resolution passed
+            // but expired unexecuted is left for the claim period to reclaim.
+            if let Some(dispute) = escrow.dispute {
+                if self.state(dispute) != Some(ProposalState::Expired) {
+                    return Err(GovernorError::EscrowDisputed);
+                }
+                self.escrow_disputes.remove(dispute);
+            } else if escrow.objected {
+                return Err(GovernorError::DisbursementObjected);
+            } else if escrow.arbiter.is_some() && !escrow.attested {
+                return Err(GovernorError::EscrowNotAttested);
+            }
+
+            self.do_clawback(proposal_id)
+        }
+
+        /// Approves the payout of a proposal whose voting period has ended. Only
+        /// needed for payouts above `cosign_threshold`.
+        #[ink(message, selector = 0xDE52ED2A)]
+        pub fn cosign(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            let signer = self.env().caller();
+            if !self.get_cosigners().contains(&signer) {
+                return Err(GovernorError::NotCosigner);
+            }
+            let now = self.now();
+            if self.pending_durations.contains(proposal_id) || now < proposal.vote_end {
+                return Err(GovernorError::VotePeriodNotEnded {
+                    ends_at: proposal.vote_end,
+                    now,
+                });
+            }
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+            if status.has(STATUS_EXECUTED) {
+                return Err(GovernorError::ProposalAlreadyExecuted);
+            }
+            if self.cosignatures.contains((proposal_id, signer)) {
+                return Err(GovernorError::AlreadyCosigned);
+            }
+
+            self.cosignatures.insert((proposal_id, signer), &());
+            self.env().emit_event(Cosigned {
+                proposal_id,
+                signer,
+            });
+
+            Ok(())
+        }
+
+        /// Guardian sign-off releasing the next tranche of a milestone payout.
+        #[ink(message, selector = 0xEF53D893)]
+        pub fn release_tranche(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.ensure_role(GUARDIAN_ROLE)?;
+            self.do_release_tranche(proposal_id)
+        }
+
+        #[ink(message, selector = 0xFA6792C7)]
+        pub fn cancel_milestones(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.ensure_role(GUARDIAN_ROLE)?;
+            self.do_cancel_milestones(proposal_id)
+        }
+
+        #[ink(message, selector = 0x8D1593AB)]
+        pub fn get_milestone_plan(&self, proposal_id: ProposalId) -> Option<MilestonePlan> {
+            self.milestone_plans.get(proposal_id)
+        }
+
+        /// Posts the hash of a deliverable report for the grant paid out by an
+        /// executed proposal. Only the grant's recipient can report, and only
+        /// until the DAO closes the grant.
+        #[ink(message, selector = 0xB8351C44)]
+        pub fn submit_grant_report(&mut self, proposal_id: ProposalId, report: Hash) -> Result<()> {
+            let mut grant = self.grant(proposal_id)?;
+            if self.env().caller() != grant.recipient {
+                return Err(GovernorError::NotRecipient);
+            }
+            if grant.status != GrantStatus::Open {
+                return Err(GovernorError::GrantClosed);
+            }
+            if grant.reports.len() >= MAX_GRANT_REPORTS as usize {
+                return Err(GovernorError::TooManyReports);
+            }
+
+            grant.reports.push(report);
+            self.grants.insert(proposal_id, &grant);
+            self.env().emit_event(GrantReportSubmitted {
+                proposal_id,
+                report,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message, selector = 0xBAD3D604)]
+        pub fn get_grant(&self, proposal_id: ProposalId) -> Option<GrantRecord> {
+            self.grant(proposal_id).ok()
+        }
+
+        #[ink(message, selector = 0x52CDE755)]
+        pub fn get_cosigners(&self) -> Vec<AccountId> {
+            self.cosigners.get().unwrap_or_default()
+        }
+
+        /// Number of current cosigners that approved the proposal.
+        #[ink(message, selector = 0x706A83D9)]
+        pub fn get_cosignature_count(&self, proposal_id: ProposalId) -> u32 {
+            self.get_cosigners()
+                .iter()
+                .filter(|signer| self.cosignatures.contains((proposal_id, **signer)))
+                .count() as u32
+        }
+
+        /// Runs every check `execute` performs without changing any state, returning
+        /// the error `execute` would fail with, if any.
+        #[ink(message, selector = 0x80D8C8C6)]
+        pub fn simulate_execute(&self, proposal_id: ProposalId) -> Result<()> {
+            self.executable_proposal(proposal_id).map(|_| ())
+        }
+
+        /// Returns every unmet condition for executing a proposal rather than only
+        /// the first, so UIs can show a complete checklist. Empty once `execute`
+        /// would succeed for the caller.
+        #[ink(message, selector = 0x44A0579E)]
+        pub fn why_not_executable(&self, proposal_id: ProposalId) -> Vec<GovernorError> {
+            let proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                None => return ink::prelude::vec![GovernorError::ProposalNotFound],
+            };
+            let mut status = self.proposal_status.get(proposal_id).unwrap_or_default();
+
+            self.execution_blockers(proposal_id, &proposal, &mut status)
+                .1
+        }
+
+        /// Adds the balances held by the proposal's for and against addresses to the
+        /// tally once the voting period has ended and records the proposal's
+        /// `Outcome`, so later reads no longer query the governance token. An
+        /// encrypted vote whose key wasn't revealed within `BALLOT_REVEAL_PERIOD`
+        /// fails.
+        #[ink(message, selector = 0x40656E2B)]
+        pub fn finalize(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.activate_queued();
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            if self.pending_durations.contains(proposal_id) {
+                return Err(GovernorError::ProposalPending);
+            }
+            if self.now() < proposal.vote_end {
+                return Err(GovernorError::VotePeriodNotEnded {
+                    ends_at: proposal.vote_end,
+                    now: self.now(),
+                });
+            }
+            let key_missed = !self.ballot_keys.contains(proposal_id)
+                && self.now() >= proposal.vote_end.saturating_add(BALLOT_REVEAL_PERIOD);
+            let tallied = self.ballots_fully_tallied(proposal_id);
+            if !tallied && !key_missed {
+                return Err(GovernorError::TallyIncomplete);
+            }
+            let mut status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+            if status.has(STATUS_FINALIZED) {
+                return Err(GovernorError::ProposalAlreadyFinalized);
+            }
+
+            let mut outcome = self.finalize_status(proposal_id, &proposal, &mut status);
+            if !tallied {
+                outcome.passed = false;
+            }
+            self.proposal_status.insert(proposal_id, &status);
+            self.store_outcome(proposal_id, &proposal, &outcome)?;
+
+            Ok(())
+        }
+
+        #[ink(message, selector = 0x5C417B0C)]
+        pub fn get_proposal_vote(&self, proposal_id: ProposalId) -> Option<ProposalVote> {
+            let proposal = self.proposals.get(proposal_id)?;
+            let mut status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if !status.has(STATUS_FINALIZED) {
+                self.add_deposit_weights(&proposal, &mut status, &mut WeightCache::default());
+            }
+
+            Some(status.votes())
+        }
+
+        #[ink(message, selector = 0x137A03B0)]
+        pub fn get_receipt(
+            &self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+        ) -> Option<VoteReceipt> {
+            self.receipts.get((proposal_id, voter))
+        }
+
+        /// Returns `(proposal, vote, weight, cast_at)` for the votes `account` cast,
+        /// oldest first. At most `MAX_PAGE_SIZE` entries are returned per call.
+        #[ink(message, selector = 0x04EB76C1)]
+        pub fn get_voting_history(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ProposalId, VoteType, Balance, Timestamp)> {
+            let len = self.voting_history_len.get(account).unwrap_or(0);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+
+            (offset..end)
+                .filter_map(|index| {
+                    let proposal_id = self.voting_history.get((account, index))?;
+                    let receipt = self.receipts.get((proposal_id, account))?;
+                    Some((proposal_id, receipt.vote, receipt.weight, receipt.cast_at))
+                })
+                .collect()
+        }
+
+        #[ink(message, selector = 0x2C729F82)]
+        pub fn get_outcome(&self, proposal_id: ProposalId) -> Option<Outcome> {
+            self.outcomes.get(proposal_id)
+        }
+
+        #[ink(message, selector = 0xFAE85C13)]
+        pub fn get_proposal_status(&self, proposal_id: ProposalId) -> Option<ProposalStatus> {
+            self.proposal_status.get(proposal_id)
+        }
+
+        #[ink(message, selector = 0xBA4DC5EC)]
+        pub fn get_proposal(&self, proposal_id: u32) -> Option<Proposal> {
+            self.proposals.get(proposal_id)
+        }
+
+        /// Shifts the governor's clock `offset` milliseconds ahead of the block
+        /// timestamp, so tests and local UIs can skip voting periods and
+        /// timelocks. The clock only moves forward, so `offset` can't be lower
+        /// than the current one. Guardian only (the deployer holds the role),
+        /// and only compiled with the `test-utils` feature.
+        #[cfg(feature = "test-utils")]
+        #[ink(message, selector = 0x3A460612)]
+        pub fn set_time_offset(&mut self, offset: u64) -> Result<()> {
+            self.ensure_role(GUARDIAN_ROLE)?;
+            if offset < self.time_offset {
+                return Err(GovernorError::InvalidParameter);
+            }
+
+            self.time_offset = offset;
+            Ok(())
+        }
+
+        #[ink(message, selector = 0x0CED162A)]
+        pub fn state(&self, proposal_id: ProposalId) -> Option<ProposalState> {
+            let proposal = self.proposals.get(proposal_id)?;
+            Some(self.proposal_state(proposal_id, &proposal))
+        }
+
+        /// Returns the states of proposals `from_id` up to but excluding `to_id`,
+        /// at most `MAX_PAGE_SIZE` of them. Ids past the last proposal are left
+        /// out.
+        #[ink(message, selector = 0x38F48DD2)]
+        pub fn get_states(&self, from_id: ProposalId, to_id: ProposalId) -> Vec<ProposalState> {
+            let end = to_id
+                .min(from_id.saturating_add(MAX_PAGE_SIZE))
+                .min(self.next_proposal_id);
+            (from_id..end)
+                .filter_map(|proposal_id| self.state(proposal_id))
+                .collect()
+        }
+
+        /// Checks that every id from `from_id` up to but excluding `to_id`, at
+        /// most `MAX_PAGE_SIZE` of them and below `next_proposal_id`, has a
+        /// proposal, vote record, timeline and category reference. Returns what
+        /// is missing, e.g. to catch storage corruption after a migration.
+        #[ink(message, selector = 0x32FFA324)]
+        pub fn integrity_check(
+            &self,
+            from_id: ProposalId,
+            to_id: ProposalId,
+        ) -> Vec<(ProposalId, Inconsistency)> {
+            let end = to_id
+                .min(from_id.saturating_add(MAX_PAGE_SIZE))
+                .min(self.next_proposal_id);
+            let mut inconsistencies = Vec::new();
+            for proposal_id in from_id..end {
+                let proposal = match self.proposals.get(proposal_id) {
+                    Some(proposal) => proposal,
+                    None => {
+                        inconsistencies.push((proposal_id, Inconsistency::MissingProposal));
+                        continue;
+                    }
+                };
+                if !self.proposal_status.contains(proposal_id) {
+                    inconsistencies.push((proposal_id, Inconsistency::MissingVoteRecord));
+                }
+                if !self.timelines.contains(proposal_id) {
+                    inconsistencies.push((proposal_id, Inconsistency::MissingTimeline));
+                }
+                let reference = (proposal.category, proposal.category_number);
+                if self.references.get(reference) != Some(proposal_id) {
+                    inconsistencies.push((proposal_id, Inconsistency::ReferenceMismatch));
+                }
+            }
+            inconsistencies
+        }
+
+        /// Returns a proposal with its text, state, tallies, timeline and
+        /// outcome, so explorers need a single read per proposal.
+        #[ink(message, selector = 0x30EA97A5)]
+        pub fn get_proposal_full(&self, proposal_id: ProposalId) -> Option<ProposalFull> {
+            let proposal = self.proposals.get(proposal_id)?;
+            let state = self.proposal_state(proposal_id, &proposal);
+            Some(ProposalFull {
+                proposal,
+                text: self.proposal_texts.get(proposal_id),
+                state,
+                votes: self
+                    .proposal_status
+                    .get(proposal_id)
+                    .unwrap_or_default()
+                    .votes(),
+                timeline: self.timelines.get(proposal_id),
+                outcome: self.outcomes.get(proposal_id),
+            })
+        }
+
+        #[ink(message, selector = 0xB9594CD5)]
+        pub fn get_proposal_text(&self, proposal_id: ProposalId) -> Option<ProposalText> {
+            self.proposal_texts.get(proposal_id)
+        }
+
+        #[ink(message, selector = 0xD6C2BAE6)]
+        pub fn get_proposal_actions(&self, proposal_id: ProposalId) -> Vec<ProposalAction> {
+            self.proposal_actions.get(proposal_id).unwrap_or_default()
+        }
+
+        /// Returns the effects of executing a proposal as typed actions, starting
+        /// with its payout.
+        #[ink(message, selector = 0x26194540)]
+        pub fn get_actions(&self, proposal_id: ProposalId) -> Vec<Action> {
+            let mut actions = Vec::new();
+            let proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                None => return actions,
+            };
+
+            if proposal.amount > 0 {
+                actions.push(Action::NativeTransfer {
+                    to: proposal.to,
+                    amount: proposal.amount,
+                });
+            }
+            for action in self.get_proposal_actions(proposal_id) {
+                let action = match action {
+                    ProposalAction::ChangeParameter(change) => Action::ParameterChange(change),
+                    ProposalAction::TreasuryShare { bps, cap } => Action::NativeTransfer {
+                        to: proposal.to,
+                        amount: self.treasury_share(bps, cap),
+                    },
+                    ProposalAction::UsdPayout { usd, max_amount } => Action::NativeTransfer {
+                        to: proposal.to,
+                        amount: self.usd_to_native(usd, max_amount).unwrap_or_default(),
+                    },
+                    ProposalAction::Swap {
+                        token_in,
+                        token_out,
+                        amount_in,
+                        min_amount_out,
+                    } => {
+                        let this = self.env().account_id();
+                        match self.config.dex_router {
+                            Some(router) => Action::ContractCall {
+                                callee: router,
+                                selector: dex::SWAP_EXACT_IN,
+                                input: (token_in, token_out, amount_in, min_amount_out, this)
+                                    .encode(),
+                            },
+                            None => Action::Governance(ProposalAction::Swap {
+                                token_in,
+                                token_out,
+                                amount_in,
+                                min_amount_out,
+                            }),
+                        }
+                    }
+                    ProposalAction::CastExternalVote {
+                        governor,
+                        proposal_id,
+                        vote,
+                    } => Action::ContractCall {
+                        callee: governor,
+                        selector: governor::CAST_VOTE,
+                        input: (proposal_id, vote as u8).encode(),
+                    },
+                    ProposalAction::SweepToken { token, to } => Action::TokenTransfer {
+                        token,
+                        to,
+                        amount: match self.code_intact(token) {
+                            true => PSP22Ref::balance_of(&token, self.env().account_id())
+                                .saturating_sub(self.reserved_tokens(token)),
+                            false => 0,
+                        },
+                    },
+                    ProposalAction::MilestonePayout {
+                        recipient,
+                        ref tranches,
+                    } => Action::NativeTransfer {
+                        to: recipient,
+                        amount: tranches.iter().sum(),
+                    },
+                    action => Action::Governance(action),
+                };
+                actions.push(action);
+            }
+
+            actions
+        }
+
+        /// Deletes the text, actions and per-strategy tallies of a settled
+        /// proposal, i.e. one that was executed, canceled or failed, and owes its
+        /// storage deposit back to whoever paid it. Callable by anyone.
+        #[ink(message, selector = 0xD7BFEE60)]
+        pub fn prune(&mut self, proposal_id: ProposalId) -> Result<()> {
+            if !self.proposals.contains(proposal_id) {
+                return Err(GovernorError::ProposalNotFound);
+            }
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            let failed = matches!(self.outcomes.get(proposal_id), Some(outcome) if !outcome.passed);
+            if !(status.has(STATUS_EXECUTED) || status.has(STATUS_CANCELED) || failed) {
+                return Err(GovernorError::ProposalNotSettled);
+            }
+
+            self.proposal_texts.remove(proposal_id);
+            self.proposal_actions.remove(proposal_id);
+            self.proposal_strategies.remove(proposal_id);
+            self.strategy_tallies.remove(proposal_id);
+
+            if let Some(deposit) = self.storage_deposits.get(proposal_id) {
+                self.storage_deposits.remove(proposal_id);
+                let owed = self.storage_owed(deposit.payer);
+                self.storage_owed
+                    .insert(deposit.payer, &owed.saturating_add(deposit.amount));
+                self.env().emit_event(ProposalPruned {
+                    proposal_id,
+                    payer: deposit.payer,
+                    refund: deposit.amount,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Storage deposits of pruned proposals `account` can reclaim.
+        #[ink(message, selector = 0x5DA1FDF1)]
+        pub fn storage_owed(&self, account: AccountId) -> Balance {
+            self.storage_owed.get(account).unwrap_or(0)
+        }
+
+        /// Pays the caller the storage deposits owed to them.
+        #[ink(message, selector = 0xADABBE44)]
+        pub fn reclaim_storage(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let owed = self.storage_owed(caller);
+            if owed == 0 {
+                return Err(GovernorError::NothingToReclaim);
+            }
+
+            self.storage_owed.remove(caller);
+            self.total_storage_deposits -= owed;
+            self.env()
+                .transfer(caller, owed)
+                .map_err(|_| GovernorError::TransferError)
+        }
+
+        /// Refunded bonds of passed proposals `account` can reclaim.
+        #[ink(message, selector = 0xC3BB394A)]
+        pub fn bond_owed(&self, account: AccountId) -> Balance {
+            self.bonds_owed.get(account).unwrap_or(0)
+        }
+
+        /// Pays the caller the bonds refunded to them.
+        #[ink(message, selector = 0xA5E4C258)]
+        pub fn reclaim_bond(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let owed = self.bond_owed(caller);
+            if owed == 0 {
+                return Err(GovernorError::NothingToReclaim);
+            }
+
+            self.bonds_owed.remove(caller);
+            self.total_bonded -= owed;
+            self.env()
+                .transfer(caller, owed)
+                .map_err(|_| GovernorError::TransferError)
+        }
+
+        /// Removes a shut-down governor from the chain, sending its remaining
+        /// balance to the successor. Only possible once no locked balance or
+        /// tokens, payout, bond, storage deposit or token airdrop budget is held
+        /// for anyone, no treasury funds are staked, and the pro-rata
+        /// distribution, if any, has been open for `claim_period`.
+        #[ink(message, selector = 0x476D839F)]
+        pub fn terminate(&mut self) -> Result<()> {
+            let shutdown = self
+                .shutdown
+                .clone()
+                .ok_or(GovernorError::ShutdownPending)?;
+
+            if shutdown.airdrop_id.is_some() {
+                let claim_period = self.config.claim_period;
+                if self.now() < shutdown.at + claim_period {
+                    return Err(GovernorError::ClaimPeriodNotOver);
+                }
+            }
+            if self.total_locked > 0
+                || self.total_tokens_locked > 0
+                || self.total_escrowed > 0
+                || self.total_bonded > 0
+                || self.total_storage_deposits > 0
+                || self.airdrop_reserved_token_count > 0
+                || self.total_staked > 0
+                || self.total_unbonding > 0
+            {
+                return Err(GovernorError::ShutdownPending);
+            }
+
+            self.env().terminate_contract(shutdown.successor)
+        }
+
+        #[ink(message, selector = 0xB7BEF875)]
+        pub fn get_treasury_balances(&self) -> TreasuryBalances {
+            TreasuryBalances {
+                liquid: self.treasury_balance(),
+                staked: self.total_staked,
+                unbonding: self.total_unbonding,
+            }
+        }
+
+        #[ink(message, selector = 0xBC7B2FF0)]
+        pub fn get_shutdown(&self) -> Option<Shutdown> {
+            self.shutdown.clone()
+        }
+
+        #[ink(message, selector = 0x38539121)]
+        pub fn get_active_proposals(&self) -> Vec<ProposalId> {
+            self.active_proposals.clone()
+        }
+
+        /// Hands the guardian role over as scheduled by an executed
+        /// `RotateGuardian` proposal, once its delay has passed. Callable by
+        /// anyone.
+        #[ink(message, selector = 0x19828543)]
+        pub fn complete_guardian_rotation(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let handover = self
+                .guardian_handovers
+                .get(proposal_id)
+                .ok_or(GovernorError::HandoverNotFound)?;
+            if self.now() < handover.activates_at {
+                return Err(GovernorError::HandoverDelayNotOver);
+            }
+
+            self.guardian_handovers.remove(proposal_id);
+            if let Some(outgoing) = handover.outgoing {
+                self._do_revoke_role(GUARDIAN_ROLE, outgoing);
+                self.env().emit_event(RoleChanged {
+                    proposal_id,
+                    role: GUARDIAN_ROLE,
+                    account: outgoing,
+                    granted: false,
+                });
+            }
+            self._setup_role(GUARDIAN_ROLE, handover.incoming);
+            self.env().emit_event(RoleChanged {
+                proposal_id,
+                role: GUARDIAN_ROLE,
+                account: handover.incoming,
+                granted: true,
+            });
+
+            Ok(())
+        }
+
+        /// Calls off a scheduled guardian rotation before its handover, e.g. one
+        /// pushed through by a hostile majority. Guardian only, other than the
+        /// guardian being rotated out.
+        #[ink(message, selector = 0x21316EDC)]
+        pub fn cancel_guardian_rotation(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.ensure_role(GUARDIAN_ROLE)?;
+            let handover = self
+                .guardian_handovers
+                .get(proposal_id)
+                .ok_or(GovernorError::HandoverNotFound)?;
+            if handover.outgoing == Some(self.env().caller()) {
+                return Err(GovernorError::MissingRole);
+            }
+
+            self.guardian_handovers.remove(proposal_id);
+            self.env().emit_event(GuardianRotationCanceled {
+                proposal_id,
+                canceled_by: self.env().caller(),
+            });
+
+            Ok(())
+        }
+
+        #[ink(message, selector = 0x5EE2024C)]
+        pub fn get_guardian_handover(&self, proposal_id: ProposalId) -> Option<GuardianHandover> {
+            self.guardian_handovers.get(proposal_id)
+        }
+
+        /// Whether guardians and council members can still use their powers;
+        /// see `guardian_powers_until`.
+        #[ink(message, selector = 0x4D601233)]
+        pub fn guardian_powers_active(&self) -> bool {
+            self.config
+                .guardian_powers_until
+                .map_or(true, |until| self.now() < until)
+        }
+
+        /// Emits `VotingEndingSoon` for a proposal whose vote ends within
+        /// `reminder_window`, once per proposal, as a trigger for notification
+        /// services. Callable by anyone.
+        #[ink(message, selector = 0xA95D2711)]
+        pub fn poke(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            let now = self.now();
+            let window = self.config.reminder_window;
+            if self.pending_durations.contains(proposal_id)
+                || window == 0
+                || now.saturating_add(window) < proposal.vote_end
+            {
+                return Err(GovernorError::ReminderNotDue);
+            }
+            if now >= proposal.vote_end {
+                return Err(GovernorError::VotePeriodEnded {
+                    ended_at: proposal.vote_end,
+                    now,
+                });
+            }
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+            if self.reminded.contains(proposal_id) {
+                return Err(GovernorError::AlreadyReminded);
+            }
+
+            self.reminded.insert(proposal_id, &());
+            self.env().emit_event(VotingEndingSoon {
+                proposal_id,
+                vote_end: proposal.vote_end,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the open proposals `account` has weight on but hasn't voted on
+        /// yet, with that weight, skipping the first `offset`. At most
+        /// `MAX_PAGE_SIZE` entries are returned per call.
+        #[ink(message, selector = 0x43DA1A7A)]
+        pub fn pending_votes(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ProposalId, Balance)> {
+            let now = self.now();
+            self.active_proposals
+                .iter()
+                .filter_map(|proposal_id| {
+                    let proposal = self.proposals.get(proposal_id)?;
+                    if now >= proposal.vote_end || self.has_voted(*proposal_id, account) {
+                        return None;
+                    }
+                    let weight = self
+                        .strategy_weights(account, *proposal_id, &proposal)
+                        .iter()
+                        .fold(0, |total: Balance, w| total.saturating_add(*w));
+                    (weight > 0).then_some((*proposal_id, weight))
+                })
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect()
+        }
+
+        /// Number of proposals waiting for a voting slot.
+        #[ink(message, selector = 0x2F087307)]
+        pub fn get_pending_count(&self) -> u32 {
+            self.pending_tail - self.pending_head
+        }
+
+        #[ink(message, selector = 0xBBF1AA58)]
+        pub fn get_proposer_record(&self, proposer: AccountId) -> ProposerRecord {
+            self.proposer_records.get(proposer).unwrap_or_default()
+        }
+
+        /// Bond `proposer` has to send with `propose`, after the discount earned
+        /// by their executed proposals.
+        #[ink(message, selector = 0x522FD500)]
+        pub fn get_required_bond(&self, proposer: AccountId) -> Balance {
+            let bond = self.config.proposal_bond;
+            let record = self.proposer_records.get(proposer).unwrap_or_default();
+            if record.executed < self.config.reputation_min_executed.max(1) {
+                return bond;
+            }
+            let finished = (record.executed + record.defeated) as Balance;
+            let discount = bond
+                .saturating_mul(self.config.max_bond_discount as Balance)
+                .saturating_mul(record.executed as Balance)
+                / (finished * MAX_BASIS_POINTS as Balance);
+            bond.saturating_sub(discount)
+        }
+
+        /// Earliest time a draft proposal can be activated, or `None` if the
+        /// proposal isn't a draft.
+        #[ink(message, selector = 0x556D458E)]
+        pub fn get_discussion_end(&self, proposal_id: ProposalId) -> Option<Timestamp> {
+            self.drafts.get(proposal_id)
+        }
+
+        #[ink(message, selector = 0x55AF706C)]
+        pub fn get_translations(&self, proposal_id: ProposalId) -> Vec<(String, Hash)> {
+            self.translations.get(proposal_id).unwrap_or_default()
+        }
+
+        /// Whether `content` hashes, with Blake2x256, to the translation
+        /// registered for `locale`.
+        #[ink(message, selector = 0xC4CDA908)]
+        pub fn verify_translation(
+            &self,
+            proposal_id: ProposalId,
+            locale: String,
+            content: Vec<u8>,
+        ) -> bool {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&content, &mut output);
+            self.translations
+                .get(proposal_id)
+                .unwrap_or_default()
+                .iter()
+                .any(|(existing, hash)| *existing == locale && *hash == Hash::from(output))
+        }
+
+        /// Whether `content` hashes, with Blake2x256, to the proposal's content
+        /// hash. `content` is the SCALE encoding of `(to, amount, title,
+        /// description, category, actions)` as stored, i.e. with whole-token
+        /// amounts converted to smallest units and `WholeTokenAmounts` dropped.
+        #[ink(message, selector = 0xFCDFD7AD)]
+        pub fn verify_content(&self, proposal_id: ProposalId, content: Vec<u8>) -> bool {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&content, &mut output);
+            self.content_hashes.get(proposal_id) == Some(Hash::from(output))
+        }
+
+        #[ink(message, selector = 0x54325F30)]
+        pub fn get_content_hash(&self, proposal_id: ProposalId) -> Option<Hash> {
+            self.content_hashes.get(proposal_id)
+        }
+
+        #[ink(message, selector = 0x301AC5C1)]
+        pub fn execution_attempts(&self, proposal_id: ProposalId) -> ExecutionAttempts {
+            self.failed_executions.get(proposal_id).unwrap_or_default()
+        }
+
+        /// Contract calls made by an executed proposal's actions, in order.
+        /// Execution is atomic: a call whose failure its action can't accept
+        /// reverts the execution together with its log, and `execute` returns
+        /// the error instead.
+        #[ink(message, selector = 0xEF774A60)]
+        pub fn get_execution_log(&self, proposal_id: ProposalId) -> Vec<CallRecord> {
+            self.execution_logs.get(proposal_id).unwrap_or_default()
+        }
+
+        #[ink(message, selector = 0xB89686B9)]
+        pub fn get_timeline(&self, proposal_id: ProposalId) -> Option<Timeline> {
+            self.timelines.get(proposal_id)
+        }
+
+        /// Returns the human-friendly reference of a proposal, e.g. `TREASURY-12`.
+        #[ink(message, selector = 0xF3A0D930)]
+        pub fn get_reference(&self, proposal_id: ProposalId) -> Option<String> {
+            let proposal = self.proposals.get(proposal_id)?;
+            Some(format!(
+                "{}-{}",
+                proposal.category, proposal.category_number
+            ))
+        }
+
+        #[ink(message, selector = 0x14334541)]
+        pub fn resolve_reference(&self, category: String, number: u32) -> Option<ProposalId> {
+            self.references.get((category, number))
+        }
+
+        #[ink(message, selector = 0x58932521)]
+        pub fn get_category_template(&self, category: String) -> Option<CategoryTemplate> {
+            self.category_templates.get(category)
+        }
+
+        #[ink(message, selector = 0xD0707806)]
+        pub fn get_config(&self) -> GovernorConfig {
+            self.config.clone()
+        }
+
+        /// Name, symbol and decimals of the governance token, cached when the
+        /// governor was deployed or the token last changed.
+        #[ink(message, selector = 0xD05408D2)]
+        pub fn token_info(&self) -> TokenInfo {
+            self.governance_token_info.clone()
+        }
+
+        /// The configuration to pass to `new_with_state` to deploy a mirror of
+        /// this governor.
+        #[ink(message, selector = 0x4D153C9A)]
+        pub fn export_config(&self) -> GovernorConfig {
+            self.config.clone()
+        }
+
+        /// Council, cosigners and strategies to pass to `new_with_state`
+        /// alongside `export_config`.
+        #[ink(message, selector = 0xC6E9A2B9)]
+        pub fn export_state(&self) -> InitialState {
+            InitialState {
+                council: self.council.clone(),
+                council_term_end: self.council_term_end,
+                council_schedule: self.council_schedule.clone(),
+                cosigners: self.get_cosigners(),
+                strategies: self.strategies.clone(),
+            }
+        }
+
+        /// Returns changelog entries with `seq >= cursor`, oldest first and at most
+        /// `MAX_PAGE_SIZE` of them. Only the last `CHANGELOG_CAPACITY` entries are
+        /// kept; older ones are skipped.
+        #[ink(message, selector = 0xCCE7F01D)]
+        pub fn get_events_since(&self, cursor: u64) -> Vec<ChangeEntry> {
+            let oldest = self.changelog_head.saturating_sub(CHANGELOG_CAPACITY);
+            let start = cursor.max(oldest);
+            let end = start
+                .saturating_add(MAX_PAGE_SIZE as u64)
+                .min(self.changelog_head);
+
+            (start..end)
+                .filter_map(|seq| self.changelog.get(seq % CHANGELOG_CAPACITY))
+                .collect()
+        }
+
+        #[ink(message, selector = 0xF15DD5B2)]
+        pub fn get_proposals_size(&self) -> ProposalId {
+            self.next_proposal_id
+        }
+
+        fn executable_proposal(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Result<(Proposal, ProposalStatus, Outcome)> {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            let mut status = self.proposal_status.get(proposal_id).unwrap_or_default();
+
+            let (outcome, blockers) = self.execution_blockers(proposal_id, &proposal, &mut status);
+            match blockers.into_iter().next() {
+                Some(error) => Err(error),
+                None => Ok((proposal, status, outcome)),
+            }
+        }
+
+        /// Every condition keeping a proposal from being executed by the caller,
+        /// in the order `execute` checks them.
+        fn execution_blockers(
+            &self,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+            status: &mut ProposalStatus,
+        ) -> (Outcome, Vec<GovernorError>) {
+            let mut blockers = Vec::new();
+
+            if let Err(error) = self.ensure_not_shut_down() {
+                blockers.push(error);
+            }
+            if self.pending_durations.contains(proposal_id) {
+                blockers.push(GovernorError::ProposalPending);
+            }
+            if !status.has(STATUS_FINALIZED) && !self.ballots_fully_tallied(proposal_id) {
+                blockers.push(GovernorError::TallyIncomplete);
+            }
+            if status.has(STATUS_CANCELED) {
+                blockers.push(GovernorError::ProposalCanceled);
+            }
+            if status.has(STATUS_EXECUTED) {
+                blockers.push(GovernorError::ProposalAlreadyExecuted);
+            }
+            if self.pauses.contains(proposal_id) {
+                blockers.push(GovernorError::ProposalPaused);
+            }
+
+            let now = self.now();
+
+            if now < proposal.vote_end {
+                blockers.push(GovernorError::VotePeriodNotEnded {
+                    ends_at: proposal.vote_end,
+                    now,
+                });
+            }
+
+            let executable_at = proposal.vote_end + proposal.timelock;
+            if now < executable_at {
+                blockers.push(GovernorError::TimelockNotElapsed { executable_at, now });
+            }
+            if proposal.execution_window > 0 && now >= executable_at + proposal.execution_window {
+                blockers.push(GovernorError::ProposalExpired);
+            }
+            if now < executable_at + proposal.executor_grace_period {
+                if let Err(error) = self.ensure_role(EXECUTOR_ROLE) {
+                    blockers.push(error);
+                }
+            }
+
+            let outcome = match self.outcomes.get(proposal_id) {
+                Some(outcome) => outcome,
+                None => self.finalize_status(proposal_id, proposal, status),
+            };
+
+            let rule = proposal.tally_rule;
+            if !rule.quorum_reached(&outcome.votes(), outcome.quorum_base) {
+                blockers.push(GovernorError::QuorumNotReached {
+                    counted: rule.counted(&outcome.votes()),
+                    quorum: outcome.quorum_base,
+                });
+            }
+            if !rule.majority_reached(&outcome.votes(), proposal.approval_threshold) {
+                blockers.push(GovernorError::ProposalNotAccepted);
+            }
+
+            let needs_cosigning = matches!(
+                proposal.cosign_threshold,
+                Some(threshold) if proposal.amount > threshold
+            );
+            if needs_cosigning
+                && self.get_cosignature_count(proposal_id) < proposal.required_cosignatures
+            {
+                blockers.push(GovernorError::CosignaturesMissing);
+            }
+
+            let mut payout = proposal.amount;
+            for action in self
+                .proposal_actions
+                .get(proposal_id)
+                .unwrap_or_default()
+                .iter()
+            {
+                match *action {
+                    ProposalAction::UsdPayout { usd, max_amount } => {
+                        match self.usd_to_native(usd, max_amount) {
+                            Ok(amount) => payout += amount,
+                            Err(error) => blockers.push(error),
+                        }
+                    }
+                    ProposalAction::Hidden(_) => blockers.push(GovernorError::PayloadHidden),
+                    _ => {}
+                }
+            }
+            if let Err(error) = self.ensure_treasury_covers(payout) {
+                blockers.push(error);
+            }
+
+            (outcome, blockers)
+        }
+
+        /// Records a failed execution of a passed proposal, which callers can retry
+        /// until it expires. Returns `Ok` so the record isn't reverted, except
+        /// while the previous failure's backoff lasts.
+        fn record_failed_execution(
+            &mut self,
+            proposal_id: ProposalId,
+            error: GovernorError,
+        ) -> Result<()> {
+            let now = self.now();
+            let mut attempts = self.failed_executions.get(proposal_id).unwrap_or_default();
+            if now < attempts.next_retry_at {
+                return Err(error);
+            }
+
+            let delay = RETRY_BASE_DELAY
+                .saturating_mul(1 << attempts.count.min(16))
+                .min(MAX_RETRY_DELAY);
+            attempts.count += 1;
+            attempts.last_error = Some(error);
+            attempts.last_attempt = now;
+            attempts.next_retry_at = now + delay;
+            self.failed_executions.insert(proposal_id, &attempts);
+            self.env().emit_event(ExecutionFailed {
+                proposal_id,
+                executor: self.env().caller(),
+                error,
+                attempt: attempts.count,
+            });
+
+            Ok(())
+        }
+
+        /// Calls `selector` on the registered extension, if any, with `args` encoded
+        /// as the message arguments. See the `hooks` module for the contract.
+        fn run_hook<Args: scale::Encode>(&self, selector: [u8; 4], args: Args) -> Result<()> {
+            let extension = match self.config.extension {
+                Some(extension) => extension,
+                None => return Ok(()),
+            };
+            self.ensure_code_intact(extension)?;
+
+            let allowed = build_call::<ink::env::DefaultEnvironment>()
+                .call(extension)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(args))
+                .returns::<bool>()
+                .try_invoke();
+
+            match allowed {
+                Ok(Ok(true)) => Ok(()),
+                _ => Err(GovernorError::HookRejected),
+            }
+        }
+
+        /// Asks the eligibility checker, if any, whether `account` may take part.
+        /// A failing or trapping checker counts as a refusal.
+        fn ensure_eligible(&self, account: AccountId) -> Result<()> {
+            let checker = match self.config.eligibility_checker {
+                Some(checker) => checker,
+                None => return Ok(()),
+            };
+            self.ensure_code_intact(checker)?;
+
+            let input = ExecutionInput::new(Selector::new(hooks::IS_ELIGIBLE)).push_arg(account);
+            let eligible = build_call::<ink::env::DefaultEnvironment>()
+                .call(checker)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(input)
+                .returns::<bool>()
+                .try_invoke();
+
+            match eligible {
+                Ok(Ok(true)) => Ok(()),
+                _ => Err(GovernorError::NotEligible),
+            }
+        }
+
+        /// Applies the `contract_voters` policy if `voter` is a contract.
+        fn ensure_contract_voter_allowed(&self, voter: AccountId) -> Result<()> {
+            if !self.env().is_contract(&voter) {
+                return Ok(());
+            }
+            let allowed = match self.config.contract_voters {
+                ContractVoters::Allowed => true,
+                ContractVoters::Denied => false,
+                ContractVoters::AllowListed => match self.env().code_hash(&voter) {
+                    Ok(code_hash) => self.allowed_voter_code.contains(code_hash),
+                    Err(_) => false,
+                },
+            };
+            if !allowed {
+                return Err(GovernorError::ContractVoterNotAllowed);
+            }
+            Ok(())
+        }
+
+        /// Frees the slots of proposals whose vote ended and opens queued
+        /// proposals, oldest first, while slots are available. Also opens the
+        /// council election once it is due. Runs at the start of every message
+        /// that depends on which proposals are open.
+        fn activate_queued(&mut self) {
+            self.open_council_election_if_due();
+
+            let now = self.now();
+            let proposals = &self.proposals;
+            self.active_proposals.retain(|id| {
+                proposals
+                    .get(id)
+                    .map_or(false, |proposal| proposal.vote_end > now)
+            });
+
+            while self.active_proposals.len() < self.config.max_active_proposals as usize
+                && self.pending_head < self.pending_tail
+            {
+                let id = match self.pending_queue.get(self.pending_head) {
+                    Some(id) => id,
+                    None => break,
+                };
+                self.pending_queue.remove(self.pending_head);
+                self.pending_head += 1;
+
+                let duration = match self.pending_durations.get(id) {
+                    Some(duration) => duration,
+                    // Canceled while queued.
+                    None => continue,
+                };
+                self.pending_durations.remove(id);
+                self.open_proposal(id, duration);
+            }
+        }
+
+        /// Queues a pending proposal to open once an active slot frees up.
+        fn enqueue(&mut self, proposal_id: ProposalId, duration: u64) {
+            self.pending_queue.insert(self.pending_tail, &proposal_id);
+            self.pending_tail += 1;
+            self.pending_durations.insert(proposal_id, &duration);
+        }
+
+        /// Starts the vote of a proposal that was pending.
+        fn open_proposal(&mut self, proposal_id: ProposalId, duration: u64) {
+            let mut proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                None => return,
+            };
+            let now = self.now();
+            proposal.vote_start = now;
+            proposal.vote_end = now + duration * ONE_MINUTE;
+            self.proposals.insert(proposal_id, &proposal);
+            self.update_timeline(proposal_id, |timeline, now| {
+                timeline.active = now;
+                timeline.end = proposal.vote_end;
+                timeline.eta = proposal.vote_end + proposal.timelock;
+            });
+            self.active_proposals.push(proposal_id);
+
+            self.env().emit_event(ProposalActivated {
+                proposal_id,
+                vote_start: proposal.vote_start,
+                vote_end: proposal.vote_end,
+            });
+        }
+
+        /// Creates the next council election as a proposal of the governor itself
+        /// in the `COUNCIL` category. It bypasses the active proposal cap.
+        fn open_council_election_if_due(&mut self) {
+            let schedule = match self.council_schedule {
+                Some(ref schedule) => schedule.clone(),
+                None => return,
+            };
+            let now = self.now();
+            if self.council_election.is_some()
+                || self.shutdown.is_some()
+                || now.saturating_add(schedule.election_lead) < self.council_term_end
+            {
+                return;
+            }
+
+            let action = ProposalAction::Election {
+                seats: schedule.seats,
+                nomination_period: schedule.nomination_period,
+            };
+            let id = self.create_system_proposal(
+                self.env().account_id(),
+                COUNCIL_CATEGORY,
+                String::from("Council election"),
+                schedule.voting_duration,
+                0,
+                ink::prelude::vec![action],
+            );
+            let election = Election {
+                seats: schedule.seats,
+                nomination_period: schedule.nomination_period,
+                candidates: Vec::new(),
+            };
+            self.elections.insert(id, &election);
+            self.council_election = Some(id);
+        }
+
+        /// Opens a proposal the governor creates on its own behalf, skipping the
+        /// checks, fees and queue of `propose`.
+        fn create_system_proposal(
+            &mut self,
+            proposer: AccountId,
+            category: &str,
+            title: String,
+            duration: u64,
+            approval_threshold: u16,
+            actions: Vec<ProposalAction>,
+        ) -> ProposalId {
+            let now = self.now();
+            let nobody = AccountId::from([0; 32]);
+            let id = self.next_proposal_id();
+            let category_number = self.next_category_number(category);
+            self.references.insert((category, category_number), &id);
+            let proposal = Proposal {
+                proposer,
+                for_address: nobody,
+                against_address: nobody,
+                to: nobody,
+                category: String::from(category),
+                category_number,
+                amount: 0,
+                vote_start: now,
+                vote_end: now + duration * ONE_MINUTE,
+                quorum: self.config.quorum,
+                approval_threshold,
+                timelock: 0,
+                executor_grace_period: 0,
+                execution_window: self.config.execution_window,
+                cosign_threshold: self.config.cosign_threshold,
+                required_cosignatures: self.config.required_cosignatures,
+                tally_rule: TallyRule::Threshold,
+            };
+            let timeline = Timeline {
+                created: now,
+                active: now,
+                end: proposal.vote_end,
+                queued: None,
+                eta: proposal.vote_end,
+                executed: None,
+                canceled: None,
+            };
+            let text = ProposalText {
+                title,
+                description: String::new(),
+            };
+
+            self.proposals.insert(id, &proposal);
+            self.proposal_status.insert(id, &ProposalStatus::default());
+            self.proposal_texts.insert(id, &text);
+            if !actions.is_empty() {
+                self.proposal_actions.insert(id, &actions);
+            }
+            self.timelines.insert(id, &timeline);
+            self.active_proposals.push(id);
+
+            self.record_change(ChangeKind::ProposalCreated, id);
+            self.env().emit_event(ProposalCreated {
+                proposal_id: id,
+                proposer,
+                category: proposal.category,
+                vote_start: proposal.vote_start,
+                vote_end: proposal.vote_end,
+            });
+
+            id
+        }
+
+        /// Seats the winners of a finalized council election, starting a new term.
+        fn rotate_council(&mut self, proposal_id: ProposalId, outcome: &Outcome) {
+            self.council_election = None;
+            let schedule = match self.council_schedule {
+                Some(ref schedule) => schedule.clone(),
+                None => return,
+            };
+            if !outcome.passed || outcome.elected.is_empty() {
+                return;
+            }
+
+            for member in self.council.clone() {
+                if !outcome.elected.contains(&member) {
+                    self._do_revoke_role(COUNCIL_ROLE, member);
+                    self.env().emit_event(CouncilChanged {
+                        proposal_id,
+                        member,
+                        joined: false,
+                    });
+                }
+            }
+            for member in outcome.elected.iter() {
+                if !self.council.contains(member) {
+                    self._setup_role(COUNCIL_ROLE, *member);
+                    self.env().emit_event(CouncilChanged {
+                        proposal_id,
+                        member: *member,
+                        joined: true,
+                    });
+                }
+            }
+            self.council = outcome.elected.clone();
+            self.council_term_end = self
+                .council_term_end
+                .max(self.now())
+                .saturating_add(schedule.term_length);
+        }
+
+        /// Identifies a proposal by what it asks for, regardless of who proposes
+        /// it or for how long.
+        fn content_hash(
+            to: AccountId,
+            amount: Balance,
+            title: &str,
+            description: &str,
+            category: &str,
+            actions: &[ProposalAction],
+        ) -> Hash {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(
+                &(to, amount, title, description, category, actions),
+                &mut output,
+            );
+            Hash::from(output)
+        }
+
+        /// Creates a proposal on behalf of `proposer`, who either called `propose`
+        /// or signed the request for `propose_by_sig`.
+        fn submit_proposal(&mut self, proposer: AccountId, request: ProposalRequest) -> Result<()> {
+            let ProposalRequest {
+                for_address,
+                against_address,
+                to,
+                title,
+                description,
+                category,
+                amount,
+                duration,
+                actions,
+            } = request;
+            self.ensure_not_shut_down()?;
+            self.ensure_account_role(PROPOSER_ROLE, proposer)?;
+            self.activate_queued();
+            self.ensure_eligible(proposer)?;
+            let (amount, actions) = if actions.contains(&ProposalAction::WholeTokenAmounts) {
+                self.to_smallest_units(amount, actions)?
+            } else {
+                (amount, actions)
+            };
+
+            if amount == 0 && actions.is_empty() {
+                return Err(GovernorError::AmountShouldNotBeZero);
+            }
+            if duration == 0 || duration > self.config.max_vote_duration {
+                return Err(GovernorError::DurationError);
+            }
+            if actions.len() > self.config.max_actions as usize
+                || actions.encoded_size() > self.config.max_payload_size as usize
+            {
+                return Err(GovernorError::PayloadTooLarge);
+            }
+            for action in actions.iter() {
+                self.validate_action(action)?;
+            }
+            let ballot_kinds = actions
+                .iter()
+                .filter(|action| {
+                    matches!(
+                        action,
+                        ProposalAction::Options(_) | ProposalAction::Election { .. }
+                    )
+                })
+                .count();
+            if ballot_kinds > 1 {
+                return Err(GovernorError::InvalidParameter);
+            }
+            // Ranked and approval ballots count as for votes, so an `Options` or
+            // `Election` proposal reaching quorum always passes. It can't carry
+            // anything the runoff or election doesn't decide, nor be voted on in
+            // secret.
+            if ballot_kinds == 1
+                && (amount > 0
+                    || actions.len() > 1
+                    || self.config.ballot_decryption_func_id.is_some())
+            {
+                return Err(GovernorError::InvalidParameter);
+            }
+            let variable_payouts = actions
+                .iter()
+                .filter(|action| {
+                    matches!(
+                        action,
+                        ProposalAction::TreasuryShare { .. } | ProposalAction::UsdPayout { .. }
+                    )
+                })
+                .count();
+            if variable_payouts > 1 || (variable_payouts == 1 && amount > 0) {
+                return Err(GovernorError::InvalidParameter);
+            }
+            let hidden = actions
+                .iter()
+                .filter(|action| matches!(action, ProposalAction::Hidden(_)))
+                .count();
+            if hidden > 1 {
+                return Err(GovernorError::InvalidParameter);
+            }
+            // A proposal schedules at most one handover.
+            let rotations = actions
+                .iter()
+                .filter(|action| matches!(action, ProposalAction::RotateGuardian { .. }))
+                .count();
+            if rotations > 1 {
+                return Err(GovernorError::InvalidParameter);
+            }
+            let arbiters = actions
+                .iter()
+                .filter(|action| matches!(action, ProposalAction::Arbitrated(_)))
+                .count();
+            if arbiters > 1 || (arbiters == 1 && amount == 0 && variable_payouts == 0) {
+                return Err(GovernorError::InvalidParameter);
+            }
+            let referenda = actions
+                .iter()
+                .filter(|action| {
+                    matches!(
+                        action,
+                        ProposalAction::SubmitReferendum { .. } | ProposalAction::LinkReferendum(_)
+                    )
+                })
+                .count();
+            if referenda > 1 {
+                return Err(GovernorError::InvalidParameter);
+            }
+            if !Self::is_valid_category(&category) {
+                return Err(GovernorError::InvalidCategory);
+            }
+            if self.config.validate_treasury_on_propose {
+                self.ensure_treasury_covers(amount)?;
+            }
+            let content_hash =
+                Self::content_hash(to, amount, &title, &description, &category, &actions);
+            if let Some(defeated_at) = self.defeated_at.get(content_hash) {
+                if self.now() < defeated_at + self.config.resubmission_cooldown {
+                    return Err(GovernorError::DuplicateProposal);
+                }
+            }
+            let stored_bytes = title.len() + description.len() + actions.encoded_size();
+            let storage_deposit =
+                (stored_bytes as Balance).saturating_mul(self.config.storage_deposit_per_byte);
+            let bond = self.get_required_bond(proposer);
+            if self.env().transferred_value() != bond.saturating_add(storage_deposit) {
+                return Err(GovernorError::IncorrectBond);
+            }
+
+            let id = self.next_proposal_id();
+            if storage_deposit > 0 {
+                let deposit = StorageDeposit {
+                    payer: self.env().caller(),
+                    amount: storage_deposit,
+                };
+                self.storage_deposits.insert(id, &deposit);
+                self.total_storage_deposits += storage_deposit;
+            }
+            if bond > 0 {
+                self.bonds.insert(id, &bond);
+                self.total_bonded += bond;
+            }
+            if self.config.proposal_fee > 0 {
+                self.burn_proposal_fee(id, proposer)?;
+            }
+            let category_number = self.next_category_number(&category);
+            self.references
+                .insert((category.clone(), category_number), &id);
+
+            let template = self.category_templates.get(&category);
+            let quorum = template
+                .as_ref()
+                .and_then(|template| template.quorum)
+                .unwrap_or(self.config.quorum);
+            let mut approval_threshold = template
+                .as_ref()
+                .and_then(|template| template.approval_threshold)
+                .unwrap_or(self.config.approval_threshold);
+            let mut tally_rule = template
+                .and_then(|template| template.tally_rule)
+                .unwrap_or_default();
+            if actions.iter().any(|action| {
+                matches!(
+                    action,
+                    ProposalAction::Shutdown { .. } | ProposalAction::MigrateTo(_)
+                )
+            }) {
+                approval_threshold = approval_threshold.max(SUPER_MAJORITY_THRESHOLD);
+                tally_rule = TallyRule::Threshold;
+            }
+
+            let now = self.now();
+            let draft = self.config.discussion_period > 0 || self.config.required_sponsors > 0;
+            let active =
+                !draft && self.active_proposals.len() < self.config.max_active_proposals as usize;
+            let (vote_start, vote_end) = if active {
+                (now, now + duration * ONE_MINUTE)
+            } else {
+                (0, 0)
+            };
+            let proposal = Proposal {
+                proposer,
+                for_address,
+                against_address,
+                to,
+                category,
+                category_number,
+                amount,
+                vote_start,
+                vote_end,
+                quorum,
+                approval_threshold,
+                timelock: self.config.timelock,
+                executor_grace_period: self.config.executor_grace_period,
+                execution_window: self.config.execution_window,
+                cosign_threshold: self.config.cosign_threshold,
+                required_cosignatures: self.config.required_cosignatures,
+                tally_rule,
+            };
+
+            self.proposals.insert(id, &proposal);
+            self.proposal_status.insert(id, &ProposalStatus::default());
+            let timeline = Timeline {
+                created: now,
+                active: proposal.vote_start,
+                end: proposal.vote_end,
+                queued: None,
+                eta: proposal.vote_end + proposal.timelock,
+                executed: None,
+                canceled: None,
+            };
+            self.timelines.insert(id, &timeline);
+            self.content_hashes.insert(id, &content_hash);
+            if let Some(func_id) = self.config.ballot_decryption_func_id {
+                self.encrypted_proposals.insert(id, &func_id);
+            }
+            if active {
+                self.active_proposals.push(id);
+            } else if draft {
+                self.pending_durations.insert(id, &duration);
+                self.drafts
+                    .insert(id, &(now + self.config.discussion_period));
+            } else {
+                self.enqueue(id, duration);
+            }
+            if !self.strategies.is_empty() {
+                self.proposal_strategies.insert(id, &self.strategies);
+            }
+            self.proposal_texts
+                .insert(id, &ProposalText { title, description });
+            if !actions.is_empty() {
+                self.proposal_actions.insert(id, &actions);
+            }
+            for action in actions.iter() {
+                match *action {
+                    ProposalAction::Options(ref options) => {
+                        self.ranked_options.insert(id, &(options.len() as u8));
+                    }
+                    ProposalAction::Election {
+                        seats,
+                        nomination_period,
+                    } => {
+                        let election = Election {
+                            seats,
+                            nomination_period,
+                            candidates: Vec::new(),
+                        };
+                        self.elections.insert(id, &election);
+                    }
+                    _ => {}
+                }
+            }
+
+            self.run_hook(hooks::ON_PROPOSE, (id, proposer))?;
+            self.record_change(ChangeKind::ProposalCreated, id);
+            self.env().emit_event(ProposalCreated {
+                proposal_id: id,
+                proposer,
+                category: proposal.category,
+                vote_start: proposal.vote_start,
+                vote_end: proposal.vote_end,
+            });
+
+            Ok(())
+        }
+
+        /// Checks that `signer` signed `payload` for `signed.operation` with
+        /// their current nonce before the deadline, then uses the nonce up so
+        /// the signature can't be replayed.
+        fn use_signature<T: scale::Encode>(
+            &mut self,
+            signer: AccountId,
+            payload: &T,
+            signed: Signed,
+        ) -> Result<()> {
+            if self.now() > signed.deadline {
+                return Err(GovernorError::SignatureExpired);
+            }
+            let nonce = self.nonces.get(signer).unwrap_or(0);
+            if signed.nonce != nonce {
+                return Err(GovernorError::InvalidNonce);
+            }
+            let domain = signature::domain_separator(self.env().account_id().as_ref());
+            let message = signature::message(
+                &domain,
+                signed.operation,
+                payload,
+                signed.nonce,
+                signed.deadline,
+            );
+            match signature::signer(&signed.signature, &message) {
+                Some(account) if AccountId::from(account) == signer => {}
+                _ => return Err(GovernorError::InvalidSignature),
+            }
+
+            self.nonces.insert(signer, &(nonce + 1));
+            Ok(())
+        }
+
+        /// Counts `voter`'s ballot on a proposal, cast directly or through a
+        /// relayer.
+        fn cast_vote(
+            &mut self,
+            voter: AccountId,
+            proposal_id: ProposalId,
+            vote: VoteType,
+        ) -> Result<()> {
+            if self.encrypted_proposals.contains(proposal_id) {
+                return Err(GovernorError::BallotsEncrypted);
+            }
+            if self.ranked_options.contains(proposal_id) {
+                return Err(GovernorError::RankedBallotRequired);
+            }
+            if self.elections.contains(proposal_id) {
+                return Err(GovernorError::ApprovalBallotRequired);
+            }
+            let (mut status, weights) = self.open_ballot(proposal_id, voter)?;
+            let weight = weights
+                .iter()
+                .fold(0, |total: Balance, w| total.saturating_add(*w));
+
+            status.count(vote, weight);
+            self.proposal_status.insert(proposal_id, &status);
+            if weights.len() > 1 {
+                let mut tallies = self.strategy_tallies.get(proposal_id).unwrap_or_default();
+                tallies.resize(weights.len(), ProposalVote::default());
+                for (tally, weight) in tallies.iter_mut().zip(weights) {
+                    let votes = match vote {
+                        VoteType::For => &mut tally.for_votes,
+                        VoteType::Against => &mut tally.against_votes,
+                        VoteType::Abstain => &mut tally.abstain_votes,
+                    };
+                    *votes = votes.saturating_add(weight);
+                }
+                self.strategy_tallies.insert(proposal_id, &tallies);
+            }
+            let receipt = VoteReceipt {
+                vote,
+                weight,
+                cast_at: self.now(),
+            };
+            self.record_receipt(proposal_id, voter, &receipt);
+
+            self.run_hook(hooks::ON_VOTE, (proposal_id, voter, weight))?;
+            self.record_change(ChangeKind::VoteCast, proposal_id);
+            self.env().emit_event(VoteCast {
+                proposal_id,
+                voter,
+                vote,
+                weight,
+            });
+
+            Ok(())
+        }
+
+        /// Checks and weighs a vote of `voter`, extending their native lock to
+        /// the end of the vote. Shared by plain and encrypted ballots.
+        fn open_ballot(
+            &mut self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+        ) -> Result<(ProposalStatus, Vec<Balance>)> {
+            self.ensure_not_shut_down()?;
+            self.activate_queued();
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+
+            if self.pending_durations.contains(proposal_id) {
+                return Err(GovernorError::ProposalPending);
+            }
+            if self.now() >= proposal.vote_end {
+                return Err(GovernorError::VotePeriodEnded {
+                    ended_at: proposal.vote_end,
+                    now: self.now(),
+                });
+            }
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if status.has(STATUS_CANCELED) {
+                return Err(GovernorError::ProposalCanceled);
+            }
+
+            if self.has_voted(proposal_id, voter) {
+                return Err(GovernorError::AlreadyVoted);
+            }
+            self.ensure_eligible(voter)?;
+            self.ensure_contract_voter_allowed(voter)?;
+
+            let weights = self.strategy_weights(voter, proposal_id, &proposal);
+            let total = weights
+                .iter()
+                .fold(0, |total: Balance, w| total.saturating_add(*w));
+            if total == 0 {
+                return Err(GovernorError::NoVotingWeight);
+            }
+            if total < self.config.min_vote_weight {
+                return Err(GovernorError::VoteWeightBelowMinimum {
+                    have: total,
+                    need: self.config.min_vote_weight,
+                });
+            }
+            if self.uses_source(WeightSource::Native) {
+                let mut lock = self.native_locks.get(voter).unwrap_or_default();
+                lock.locked_until = lock.locked_until.max(proposal.vote_end);
+                self.native_locks.insert(voter, &lock);
+            }
+            if self.uses_source(WeightSource::LockedPsp22) {
+                if let Some(mut lock) = self.token_locks.get(voter) {
+                    lock.locked_until = lock.locked_until.max(proposal.vote_end);
+                    self.token_locks.insert(voter, &lock);
+                }
+            }
+
+            Ok((status, weights))
+        }
+
+        /// Whether `voter` cast a ballot of any kind on the proposal.
+        fn has_voted(&self, proposal_id: ProposalId, voter: AccountId) -> bool {
+            self.receipts.contains((proposal_id, voter))
+                || self.encrypted_voters.contains((proposal_id, voter))
+                || self.ranked_ballots.contains((proposal_id, voter))
+                || self.approval_ballots.contains((proposal_id, voter))
+        }
+
+        fn record_receipt(
+            &mut self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+            receipt: &VoteReceipt,
+        ) {
+            self.receipts.insert((proposal_id, voter), receipt);
+            let history_len = self.voting_history_len.get(voter).unwrap_or(0);
+            self.voting_history
+                .insert((voter, history_len), &proposal_id);
+            self.voting_history_len.insert(voter, &(history_len + 1));
+        }
+
+        fn ballots_fully_tallied(&self, proposal_id: ProposalId) -> bool {
+            !self.encrypted_proposals.contains(proposal_id)
+                || self.ballots_tallied.get(proposal_id).unwrap_or(0)
+                    >= self.encrypted_ballot_count.get(proposal_id).unwrap_or(0)
+        }
+
+        fn nomination_end(&self, proposal_id: ProposalId, election: &Election) -> Timestamp {
+            self.proposals
+                .get(proposal_id)
+                .map_or(0, |proposal| proposal.vote_start)
+                .saturating_add(election.nomination_period)
+        }
+
+        /// Candidates with their approved weight, most approved first. Ties keep
+        /// registration order.
+        fn election_ranking(
+            &self,
+            proposal_id: ProposalId,
+            election: &Election,
+        ) -> Vec<(AccountId, Balance)> {
+            let approvals = self.approvals.get(proposal_id).unwrap_or_default();
+            let mut ranking: Vec<(AccountId, Balance)> = election
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(index, candidate)| (*candidate, approvals.get(index).copied().unwrap_or(0)))
+                .collect();
+            ranking.sort_by(|a, b| b.1.cmp(&a.1));
+            ranking
+        }
+
+        fn update_timeline(
+            &mut self,
+            proposal_id: ProposalId,
+            update: impl FnOnce(&mut Timeline, Timestamp),
+        ) {
+            if let Some(mut timeline) = self.timelines.get(proposal_id) {
+                update(&mut timeline, self.now());
+                self.timelines.insert(proposal_id, &timeline);
+            }
+        }
+
+        fn record_change(&mut self, kind: ChangeKind, proposal_id: ProposalId) {
+            let seq = self.changelog_head;
+            let entry = ChangeEntry {
+                seq,
+                kind,
+                proposal_id,
+                at: self.now(),
+            };
+            self.changelog.insert(seq % CHANGELOG_CAPACITY, &entry);
+            self.changelog_head += 1;
+        }
+
+        fn store_outcome(
+            &mut self,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+            outcome: &Outcome,
+        ) -> Result<()> {
+            if self.outcomes.contains(proposal_id) {
+                return Ok(());
+            }
+            self.outcomes.insert(proposal_id, outcome);
+            self.close_proposal(proposal_id);
+            if self.council_election == Some(proposal_id) {
+                self.rotate_council(proposal_id, outcome);
+            }
+            if self.pending_emergency == Some(proposal_id) {
+                self.pending_emergency = None;
+            }
+            if outcome.passed {
+                self.update_timeline(proposal_id, |timeline, now| timeline.queued = Some(now));
+            } else {
+                if let Some(content_hash) = self.content_hashes.get(proposal_id) {
+                    self.defeated_at.insert(content_hash, &self.now());
+                }
+                let mut record = self
+                    .proposer_records
+                    .get(proposal.proposer)
+                    .unwrap_or_default();
+                record.defeated += 1;
+                self.proposer_records.insert(proposal.proposer, &record);
+                if !tally::majority_reached(
+                    outcome.for_votes,
+                    outcome.against_votes,
+                    SPONSOR_STRIKE_THRESHOLD,
+                ) {
+                    self.strike_sponsors(proposal_id);
+                }
+                if let Some(paused_id) = self.pause_reviews.get(proposal_id) {
+                    self.settle_pause(paused_id, true);
+                }
+                if let Some(escrow) = self.escrow_disputes.get(proposal_id) {
+                    self.escrow_disputes.remove(proposal_id);
+                    self.do_clawback(escrow)?;
+                }
+            }
+            self.record_change(ChangeKind::ProposalFinalized, proposal_id);
+            self.env().emit_event(ProposalFinalized {
+                proposal_id,
+                passed: outcome.passed,
+            });
+
+            self.settle_bond(proposal_id, proposal, outcome.passed)
+        }
+
+        /// Ends the review of a watchdog's pause. An upheld pause stays in force
+        /// and frees the watchdog to pause again; a lifted one is removed and
+        /// the watchdog's bond goes to the treasury.
+        fn settle_pause(&mut self, paused_id: ProposalId, upheld: bool) {
+            let pause = match self.pauses.get(paused_id) {
+                Some(pause) => pause,
+                None => return,
+            };
+            self.pause_reviews.remove(pause.review);
+            if let Some(mut watchdog) = self.watchdogs.get(pause.watchdog) {
+                watchdog.pausing = None;
+                if upheld {
+                    self.watchdogs.insert(pause.watchdog, &watchdog);
+                } else {
+                    self.watchdogs.remove(pause.watchdog);
+                    self.total_bonded -= watchdog.bond;
+                }
+            }
+            if !upheld {
+                self.pauses.remove(paused_id);
+            }
+
+            self.env().emit_event(PauseReviewed {
+                proposal_id: paused_id,
+                watchdog: pause.watchdog,
+                upheld,
+            });
+        }
+
+        fn strike_sponsors(&mut self, proposal_id: ProposalId) {
+            for sponsor in self.sponsors.get(proposal_id).unwrap_or_default() {
+                let strikes = self.get_sponsor_strikes(sponsor) + 1;
+                self.sponsor_strikes.insert(sponsor, &strikes);
+                self.env().emit_event(SponsorStruck {
+                    proposal_id,
+                    sponsor,
+                    strikes,
+                });
+            }
+        }
+
+        /// Refunds the bond of a passed proposal. A failed proposal's bond is
+        /// slashed: the finalizer gets `slash_bounty` of it and the treasury the rest.
+        fn settle_bond(
+            &mut self,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+            passed: bool,
+        ) -> Result<()> {
+            let bond = match self.bonds.get(proposal_id) {
+                Some(bond) => bond,
+                None => return Ok(()),
+            };
+            self.bonds.remove(proposal_id);
+
+            // The refund stays bonded until the proposer reclaims it, so a
+            // proposer refusing transfers can't block finalization.
+            if passed {
+                let owed = self.bond_owed(proposal.proposer);
+                self.bonds_owed
+                    .insert(proposal.proposer, &owed.saturating_add(bond));
+                self.env().emit_event(BondRefunded {
+                    proposal_id,
+                    proposer: proposal.proposer,
+                    amount: bond,
+                });
+                return Ok(());
+            }
+
+            self.total_bonded -= bond;
+            let finalizer = self.env().caller();
+            let bounty = bond.saturating_mul(self.config.slash_bounty as Balance)
+                / MAX_BASIS_POINTS as Balance;
+            if bounty > 0 {
+                self.env()
+                    .transfer(finalizer, bounty)
+                    .map_err(|_| GovernorError::TransferError)?;
+            }
+            self.env().emit_event(BondSlashed {
+                proposal_id,
+                finalizer,
+                bounty,
+                to_treasury: bond - bounty,
+            });
+
+            Ok(())
+        }
+
+        /// Adds the deposit weights to `status`, marks it finalized and derives the
+        /// proposal's outcome under the rules recorded in the proposal.
+        fn finalize_status(
+            &self,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+            status: &mut ProposalStatus,
+        ) -> Outcome {
+            self.add_deposit_weights(proposal, status, &mut WeightCache::default());
+            status.flags |= STATUS_FINALIZED;
+
+            let mut outcome = Outcome {
+                for_votes: status.for_votes,
+                against_votes: status.against_votes,
+                abstain_votes: status.abstain_votes,
+                quorum_base: proposal.quorum,
+                passed: false,
+                finalized_at: self.now(),
+                strategy_tallies: self.strategy_tallies.get(proposal_id).unwrap_or_default(),
+                runoff: self.ranked_options.get(proposal_id).map(|options| {
+                    let ballots = self.ranked_tallies.get(proposal_id).unwrap_or_default();
+                    tally::instant_runoff(options, &ballots)
+                }),
+                elected: match self.elections.get(proposal_id) {
+                    Some(election) => self
+                        .election_ranking(proposal_id, &election)
+                        .into_iter()
+                        .filter(|(_, approved)| *approved > 0)
+                        .take(election.seats as usize)
+                        .map(|(candidate, _)| candidate)
+                        .collect(),
+                    None => Vec::new(),
+                },
+            };
+            outcome.passed = proposal.tally_rule.passed(
+                &outcome.votes(),
+                outcome.quorum_base,
+                proposal.approval_threshold,
+            );
+
+            outcome
+        }
+
+        fn proposal_state(&self, proposal_id: ProposalId, proposal: &Proposal) -> ProposalState {
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            let window = proposal.execution_window;
+            let inputs = tally::StateInputs {
+                canceled: status.has(STATUS_CANCELED),
+                executed: status.has(STATUS_EXECUTED),
+                draft: self.drafts.contains(proposal_id),
+                queued: self.pending_durations.contains(proposal_id),
+                vote_end: proposal.vote_end,
+                passed: self.outcomes.get(proposal_id).map(|outcome| outcome.passed),
+                expires_at: (window > 0).then(|| proposal.vote_end + proposal.timelock + window),
+            };
+            tally::state(&inputs, self.now())
+        }
+
+        /// Replaces `delegator`'s delegation, if any, with one of their current
+        /// weight to `delegatee`.
+        fn set_delegation(&mut self, delegator: AccountId, delegatee: AccountId) -> Result<()> {
+            if !self.uses_source(WeightSource::Psp22) {
+                return Err(GovernorError::UnsupportedWeightSource);
+            }
+            self.ensure_code_intact(self.config.governance_token)?;
+
+            self.remove_delegation(delegator);
+            let weight = self.token_weight(self.config.governance_token, delegator);
+            let received = self.get_delegated_weight(delegatee);
+            self.checkpoint(
+                CheckpointKind::Delegated,
+                delegatee,
+                received.saturating_add(weight),
+            );
+            let delegation = Delegation {
+                delegatee,
+                weight,
+                affirmed_weight: weight,
+                affirmed_at: self.now(),
+            };
+            self.delegations.insert(delegator, &delegation);
+
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                delegatee: Some(delegatee),
+                weight,
+            });
+
+            Ok(())
+        }
+
+        fn remove_delegation(&mut self, delegator: AccountId) {
+            if let Some(delegation) = self.delegations.get(delegator) {
+                self.delegations.remove(delegator);
+                let received = self.get_delegated_weight(delegation.delegatee);
+                self.checkpoint(
+                    CheckpointKind::Delegated,
+                    delegation.delegatee,
+                    received.saturating_sub(delegation.weight),
+                );
+            }
+        }
+
+        fn checkpoint(&mut self, kind: CheckpointKind, account: AccountId, weight: Balance) {
+            if matches!(kind, CheckpointKind::Delegated) {
+                self.rank_delegate(account, weight);
+            }
+            let now = self.now();
+            let checkpoints = match kind {
+                CheckpointKind::Delegated => &mut self.delegated_checkpoints,
+                CheckpointKind::Lock => &mut self.lock_checkpoints,
+                CheckpointKind::TokenLock => &mut self.token_lock_checkpoints,
+                CheckpointKind::Lp => &mut self.lp_checkpoints,
+            };
+            let mut history = checkpoints.get(account).unwrap_or_default();
+            weight::push(&mut history, now, weight);
+            let retention = CHECKPOINT_RETENTION + self.config.min_holding_period;
+            weight::prune(&mut history, now.saturating_sub(retention));
+            checkpoints.insert(account, &history);
+        }
+
+        /// The block timestamp, shifted by the test clock offset.
+        fn now(&self) -> Timestamp {
+            self.env()
+                .block_timestamp()
+                .saturating_add(self.time_offset)
+        }
+
+        /// Moves `delegate` to its place in the leaderboard for its new weight.
+        /// A delegate only enters the leaderboard when its weight changes, so
+        /// after heavy delegates lose weight the tail may miss accounts that
+        /// have more but haven't changed since.
+        fn rank_delegate(&mut self, delegate: AccountId, weight: Balance) {
+            let mut top = self.top_delegates.get().unwrap_or_default();
+            top.retain(|(account, _)| *account != delegate);
+            let position = top.partition_point(|(_, ranked)| *ranked >= weight);
+            if weight > 0 && position < TOP_DELEGATES_CAPACITY as usize {
+                top.insert(position, (delegate, weight));
+                top.truncate(TOP_DELEGATES_CAPACITY as usize);
+            }
+            self.top_delegates.set(&top);
+        }
+
+        /// Weight of `account` on a proposal per strategy, the primary weight
+        /// source first, with multipliers applied.
+        fn strategy_weights(
+            &self,
+            account: AccountId,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+        ) -> Vec<Balance> {
+            let mut weights = Vec::new();
+            weights.push(self.snapshot_weight(self.config.weight_source, account, proposal));
+            for strategy in self
+                .proposal_strategies
+                .get(proposal_id)
+                .unwrap_or_default()
+            {
+                let weight = self.snapshot_weight(strategy.source, account, proposal);
+                weights.push(
+                    weight.saturating_mul(strategy.multiplier as Balance)
+                        / MAX_BASIS_POINTS as Balance,
+                );
+            }
+            weights
+        }
+
+        /// Whether `source` is the primary weight source or one of the current
+        /// strategies.
+        fn uses_source(&self, source: WeightSource) -> bool {
+            self.config.weight_source == source
+                || self
+                    .strategies
+                    .iter()
+                    .any(|strategy| strategy.source == source)
+        }
+
+        /// See the `weight` module for the snapshot rule. The runtime exposes no
+        /// staking history, so `RuntimeStaking` weight is read live.
+        fn snapshot_weight(
+            &self,
+            source: WeightSource,
+            account: AccountId,
+            proposal: &Proposal,
+        ) -> Balance {
+            self.weight_at(source, account, proposal.vote_start)
+        }
+
+        /// Weight from `source` recorded strictly before `snapshot` and held for
+        /// `min_holding_period` up to it. Runtime staking has no history, so it
+        /// reads the current stake.
+        fn weight_at(
+            &self,
+            source: WeightSource,
+            account: AccountId,
+            snapshot: Timestamp,
+        ) -> Balance {
+            let checkpoints = match source {
+                WeightSource::Psp22 => {
+                    let delegated = weight::held_before(
+                        &self.delegated_checkpoints.get(account).unwrap_or_default(),
+                        snapshot,
+                        self.config.min_holding_period,
+                    );
+                    return delegated.saturating_add(self.lp_weight_at(account, snapshot));
+                }
+                WeightSource::Native => self.lock_checkpoints.get(account),
+                WeightSource::LockedPsp22 => self.token_lock_checkpoints.get(account),
+                WeightSource::RuntimeStaking { func_id } => {
+                    return staked_balance(func_id, account)
+                }
+                WeightSource::Adapter(adapter) => {
+                    return self.adapter_weight(adapter, account, snapshot)
+                }
+            };
+            weight::held_before(
+                &checkpoints.unwrap_or_default(),
+                snapshot,
+                self.config.min_holding_period,
+            )
+        }
+
+        /// Native balance locked for voting, escrowed payouts and proposal bonds are
+        /// held on behalf of others and never count towards the treasury.
+        fn treasury_balance(&self) -> Balance {
+            self.env()
+                .balance()
+                .saturating_sub(self.total_locked)
+                .saturating_sub(self.total_escrowed)
+                .saturating_sub(self.total_bonded)
+                .saturating_sub(self.total_airdrop_reserved)
+                .saturating_sub(self.total_storage_deposits)
+        }
+
+        fn ensure_treasury_covers(&self, amount: Balance) -> Result<()> {
+            let available = self.treasury_balance();
+            if available < amount {
+                return Err(GovernorError::InsufficientTreasuryBalance {
+                    available,
+                    required: amount,
+                });
+            }
+            Ok(())
+        }
+
+        fn validate_action(&self, action: &ProposalAction) -> Result<()> {
+            match action {
+                ProposalAction::ChangeParameter(change) => self.validate_parameter_change(change),
+                ProposalAction::GrantRole { role, .. } if *role == GUARDIAN_ROLE => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::RotateGuardian { incoming, outgoing }
+                    if *outgoing == Some(*incoming) =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::XcmSend { .. } if self.config.xcm_func_id.is_none() => {
+                    Err(GovernorError::XcmUnavailable)
+                }
+                ProposalAction::SubmitReferendum { .. }
+                    if self.config.referenda_func_id.is_none() =>
+                {
+                    Err(GovernorError::ReferendaUnavailable)
+                }
+                ProposalAction::SetCosigners(signers) if signers.len() > MAX_COSIGNERS as usize => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::SetCategoryTemplate { category, template } => {
+                    let threshold = template.as_ref().and_then(|t| t.approval_threshold);
+                    if !Self::is_valid_category(category)
+                        || matches!(threshold, Some(value) if value >= MAX_BASIS_POINTS)
+                    {
+                        return Err(GovernorError::InvalidParameter);
+                    }
+                    Ok(())
+                }
+                ProposalAction::SweepToken { token, .. }
+                    if *token == self.config.governance_token =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::DeployToken { total_supply, .. } if *total_supply == 0 => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::RegisterAirdrop { budget, .. } if *budget == 0 => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::Election {
+                    seats,
+                    nomination_period,
+                } if *seats == 0 || *seats > MAX_CANDIDATES || *nomination_period > MAX_DELAY => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::SetCouncilSchedule(Some(schedule))
+                    if schedule.seats == 0
+                        || schedule.seats > MAX_CANDIDATES
+                        || schedule.term_length == 0
+                        || schedule.election_lead > schedule.term_length
+                        || schedule.voting_duration == 0
+                        || schedule.voting_duration > self.config.max_vote_duration
+                        || schedule.nomination_period >= schedule.voting_duration * ONE_MINUTE =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::TreasuryShare { bps, cap }
+                    if *bps == 0 || *bps > MAX_BASIS_POINTS || *cap == 0 =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::Stake { .. }
+                | ProposalAction::Unstake(_)
+                | ProposalAction::WithdrawUnbonded
+                    if self.config.pool_staking_func_id.is_none() =>
+                {
+                    Err(GovernorError::StakingUnavailable)
+                }
+                ProposalAction::Stake { amount: 0, .. } | ProposalAction::Unstake(0) => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::Swap { .. } if self.config.dex_router.is_none() => {
+                    Err(GovernorError::DexUnavailable)
+                }
+                ProposalAction::Swap {
+                    token_in,
+                    token_out,
+                    amount_in,
+                    ..
+                } if token_in == token_out || *amount_in == 0 => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::UsdPayout { .. } if self.config.price_feed.is_none() => {
+                    Err(GovernorError::PriceUnavailable)
+                }
+                ProposalAction::UsdPayout { usd, max_amount } if *usd == 0 || *max_amount == 0 => {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::Options(options)
+                    if options.len() < 2
+                        || options.len() > MAX_OPTIONS as usize
+                        || options.iter().any(|option| option.is_empty()) =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                // A source listed twice, or also the primary one, would count the
+                // same balance more than once.
+                ProposalAction::SetStrategies(strategies)
+                    if strategies.len() > MAX_STRATEGIES as usize
+                        || strategies.iter().enumerate().any(|(i, strategy)| {
+                            strategy.multiplier == 0
+                                || strategy.multiplier > MAX_STRATEGY_MULTIPLIER
+                                || strategy.source == self.config.weight_source
+                                || strategies[..i]
+                                    .iter()
+                                    .any(|other| other.source == strategy.source)
+                        }) =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::RegisterLpToken { lp_token, .. }
+                    if self.lp_pools.len() >= MAX_LP_POOLS as usize
+                        && !self.lp_pools.iter().any(|pool| pool.lp_token == *lp_token) =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                ProposalAction::MilestonePayout { tranches, .. }
+                    if tranches.is_empty()
+                        || tranches.len() > MAX_TRANCHES as usize
+                        || tranches.contains(&0) =>
+                {
+                    Err(GovernorError::InvalidParameter)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        fn apply_action(&mut self, proposal_id: ProposalId, action: &ProposalAction) -> Result<()> {
+            match *action {
+                ProposalAction::GrantRole { role, account } => {
+                    self._setup_role(role, account);
+                    self.env().emit_event(RoleChanged {
+                        proposal_id,
+                        role,
+                        account,
+                        granted: true,
+                    });
+                }
+                ProposalAction::RevokeRole { role, account } => {
+                    self._do_revoke_role(role, account);
+                    self.env().emit_event(RoleChanged {
+                        proposal_id,
+                        role,
+                        account,
+                        granted: false,
+                    });
+                }
+                ProposalAction::ChangeParameter(ref change) => {
+                    // Another proposal may have moved the config since this one
+                    // was submitted.
+                    self.validate_parameter_change(change)?;
+                    self.apply_parameter_change(change);
+                    self.env().emit_event(ParameterChanged {
+                        proposal_id,
+                        change: change.clone(),
+                    });
+                }
+                ProposalAction::XcmSend {
+                    ref dest,
+                    ref message,
+                } => {
+                    let func_id = self
+                        .config
+                        .xcm_func_id
+                        .ok_or(GovernorError::XcmUnavailable)?;
+                    if !send_xcm(func_id, dest, message) {
+                        return Err(GovernorError::XcmDispatchFailed);
+                    }
+                }
+                ProposalAction::SubmitReferendum { track, ref call } => {
+                    let func_id = self
+                        .config
+                        .referenda_func_id
+                        .ok_or(GovernorError::ReferendaUnavailable)?;
+                    let index = submit_referendum(func_id, track, call)
+                        .ok_or(GovernorError::ReferendumSubmissionFailed)?;
+                    self.referenda.insert(proposal_id, &index);
+                    self.env().emit_event(ReferendumLinked {
+                        proposal_id,
+                        index,
+                        submitted: true,
+                    });
+                }
+                ProposalAction::AllowVoterCode { code_hash, allowed } => {
+                    if allowed {
+                        self.allowed_voter_code.insert(code_hash, &());
+                    } else {
+                        self.allowed_voter_code.remove(code_hash);
+                    }
+                }
+                ProposalAction::LinkReferendum(index) => {
+                    self.referenda.insert(proposal_id, &index);
+                    self.env().emit_event(ReferendumLinked {
+                        proposal_id,
+                        index,
+                        submitted: false,
+                    });
+                }
+                ProposalAction::SetCosigners(ref signers) => self.cosigners.set(signers),
+                ProposalAction::MilestonePayout {
+                    recipient,
+                    ref tranches,
+                } => {
+                    let plan = MilestonePlan {
+                        recipient,
+                        tranches: tranches.clone(),
+                        released: 0,
+                        canceled: false,
+                    };
+                    self.milestone_plans.insert(proposal_id, &plan);
+                }
+                ProposalAction::ReleaseTranche(plan_id) => self.do_release_tranche(plan_id)?,
+                ProposalAction::CancelMilestones(plan_id) => self.do_cancel_milestones(plan_id)?,
+                ProposalAction::Clawback(escrow_id) => self.do_clawback(escrow_id)?,
+                ProposalAction::SweepToken { token, to } => {
+                    self.ensure_code_intact(token)?;
+                    let amount = PSP22Ref::balance_of(&token, self.env().account_id())
+                        .saturating_sub(self.reserved_tokens(token));
+                    self.transfer_tokens(proposal_id, token, to, amount)?;
+                    self.env().emit_event(TokensSwept { token, to, amount });
+                }
+                ProposalAction::SetExtension(extension) => self.config.extension = extension,
+                ProposalAction::SetEligibilityChecker(checker) => {
+                    self.config.eligibility_checker = checker
+                }
+                ProposalAction::DeployToken {
+                    code_hash,
+                    constructor,
+                    total_supply,
+                    ref salt,
+                    migration_root,
+                } => self.deploy_token(
+                    proposal_id,
+                    code_hash,
+                    constructor,
+                    total_supply,
+                    salt,
+                    migration_root,
+                )?,
+                ProposalAction::RegisterAirdrop {
+                    token,
+                    root,
+                    budget,
+                } => {
+                    let airdrop_id = self.register_airdrop(token, root, budget)?;
+                    self.env().emit_event(AirdropRegistered {
+                        proposal_id,
+                        airdrop_id,
+                        token,
+                        budget,
+                    });
+                }
+                ProposalAction::CloseAirdrop(airdrop_id) => self.close_airdrop(airdrop_id)?,
+                ProposalAction::RegisterLpToken { lp_token, adapter } => {
+                    self.lp_pools.retain(|pool| pool.lp_token != lp_token);
+                    if self.lp_pools.len() >= MAX_LP_POOLS as usize {
+                        return Err(GovernorError::InvalidParameter);
+                    }
+                    self.lp_pools.push(LpPool { lp_token, adapter });
+                }
+                ProposalAction::UnregisterLpToken(lp_token) => {
+                    self.lp_pools.retain(|pool| pool.lp_token != lp_token);
+                }
+                ProposalAction::SetStrategies(ref strategies) => {
+                    for strategy in strategies.iter() {
+                        if let WeightSource::Adapter(adapter) = strategy.source {
+                            if !self.strategy_adapters.contains(adapter) {
+                                return Err(GovernorError::StrategyNotRegistered);
+                            }
+                        }
+                    }
+                    self.strategies = strategies.clone();
+                }
+                ProposalAction::RegisterStrategy { adapter, code_hash } => {
+                    if self.env().code_hash(&adapter).ok() != Some(code_hash) {
+                        return Err(GovernorError::CodeHashMismatch);
+                    }
+                    self.strategy_adapters.insert(adapter, &code_hash);
+                    self.env()
+                        .emit_event(StrategyRegistered { adapter, code_hash });
+                }
+                ProposalAction::PinCode {
+                    contract,
+                    code_hash,
+                } => {
+                    match code_hash {
+                        Some(code_hash) => {
+                            if self.env().code_hash(&contract).ok() != Some(code_hash) {
+                                return Err(GovernorError::CodeHashMismatch);
+                            }
+                            self.pinned_code.insert(contract, &code_hash);
+                        }
+                        None => self.pinned_code.remove(contract),
+                    }
+                    self.env().emit_event(CodePinned {
+                        contract,
+                        code_hash,
+                    });
+                }
+                ProposalAction::DeregisterStrategy(adapter) => {
+                    if !self.strategy_adapters.contains(adapter) {
+                        return Err(GovernorError::StrategyNotRegistered);
+                    }
+                    self.strategy_adapters.remove(adapter);
+                    self.strategies
+                        .retain(|strategy| strategy.source != WeightSource::Adapter(adapter));
+                    self.env().emit_event(StrategyDeregistered { adapter });
+                }
+                ProposalAction::Options(_) | ProposalAction::Election { .. } => {}
+                ProposalAction::TreasuryShare { bps, cap } => {
+                    let amount = self.treasury_share(bps, cap);
+                    let recipient = self
+                        .proposals
+                        .get(proposal_id)
+                        .ok_or(GovernorError::ProposalNotFound)?
+                        .to;
+                    if amount > 0 {
+                        self.escrow_payout(proposal_id, recipient, amount);
+                    }
+                }
+                ProposalAction::UsdPayout { usd, max_amount } => {
+                    let amount = self.usd_to_native(usd, max_amount)?;
+                    self.ensure_treasury_covers(amount)?;
+                    let recipient = self
+                        .proposals
+                        .get(proposal_id)
+                        .ok_or(GovernorError::ProposalNotFound)?
+                        .to;
+                    if amount > 0 {
+                        self.escrow_payout(proposal_id, recipient, amount);
+                    }
+                }
+                ProposalAction::SetPriceFeed(feed) => self.config.price_feed = feed,
+                ProposalAction::Swap {
+                    token_in,
+                    token_out,
+                    amount_in,
+                    min_amount_out,
+                } => self.swap(proposal_id, token_in, token_out, amount_in, min_amount_out)?,
+                ProposalAction::SetDexRouter(router) => self.config.dex_router = router,
+                ProposalAction::Stake { pool_id, amount } => {
+                    self.ensure_treasury_covers(amount)?;
+                    let call = match self.staking_pool {
+                        None => PoolCall::Join { pool_id, amount },
+                        Some(current) if current == pool_id => PoolCall::BondExtra(amount),
+                        Some(_) => return Err(GovernorError::InvalidParameter),
+                    };
+                    self.call_pool(proposal_id, call)?;
+                    self.staking_pool = Some(pool_id);
+                    self.total_staked += amount;
+                    self.emit_stake_changed(proposal_id);
+                }
+                ProposalAction::Unstake(amount) => {
+                    if amount > self.total_staked {
+                        return Err(GovernorError::InvalidParameter);
+                    }
+                    self.call_pool(proposal_id, PoolCall::Unbond(amount))?;
+                    self.total_staked -= amount;
+                    self.total_unbonding += amount;
+                    self.emit_stake_changed(proposal_id);
+                }
+                ProposalAction::WithdrawUnbonded => {
+                    let balance_before = self.env().balance();
+                    self.call_pool(proposal_id, PoolCall::WithdrawUnbonded)?;
+                    let withdrawn = self.env().balance().saturating_sub(balance_before);
+                    self.total_unbonding = self.total_unbonding.saturating_sub(withdrawn);
+                    self.emit_stake_changed(proposal_id);
+                    if self.total_staked == 0 && self.total_unbonding == 0 {
+                        self.staking_pool = None;
+                    }
+                }
+                ProposalAction::CastExternalVote {
+                    governor,
+                    proposal_id: external_proposal_id,
+                    vote,
+                } => {
+                    let input = ExecutionInput::new(Selector::new(governor::CAST_VOTE))
+                        .push_arg(external_proposal_id)
+                        .push_arg(vote as u8);
+                    let counted = build_call::<ink::env::DefaultEnvironment>()
+                        .call(governor)
+                        .gas_limit(0)
+                        .exec_input(input)
+                        .returns::<bool>()
+                        .try_invoke();
+                    let output = match &counted {
+                        Ok(Ok(counted)) => Some(counted.encode()),
+                        _ => None,
+                    };
+                    self.log_call(proposal_id, governor, governor::CAST_VOTE, output);
+                    if !matches!(counted, Ok(Ok(true))) {
+                        return Err(GovernorError::ExternalVoteFailed);
+                    }
+                    self.env().emit_event(ExternalVoteCast {
+                        proposal_id,
+                        governor,
+                        external_proposal_id,
+                        vote,
+                    });
+                }
+                ProposalAction::SetCouncilSchedule(ref schedule) => {
+                    if schedule.is_some() && self.council_schedule.is_none() {
+                        self.council_term_end = self.now();
+                    }
+                    self.council_schedule = schedule.clone();
+                }
+                ProposalAction::Shutdown {
+                    successor,
+                    distribution_root,
+                } => self.start_shutdown(proposal_id, successor, distribution_root)?,
+                ProposalAction::MigrateTo(new_governor) => {
+                    self.migrate_to(proposal_id, new_governor)?
+                }
+                ProposalAction::SetCategoryTemplate {
+                    ref category,
+                    ref template,
+                } => match template {
+                    Some(template) => {
+                        self.category_templates.insert(category, template);
+                    }
+                    None => self.category_templates.remove(category),
+                },
+                ProposalAction::LiftPause(paused_id) => {
+                    if self.pause_reviews.get(proposal_id) != Some(paused_id) {
+                        return Err(GovernorError::InvalidParameter);
+                    }
+                    self.settle_pause(paused_id, false);
+                }
+                ProposalAction::Arbitrated(_)
+                | ProposalAction::Hidden(_)
+                | ProposalAction::WholeTokenAmounts => {}
+                ProposalAction::RotateGuardian { incoming, outgoing } => {
+                    let activates_at = self.now() + GUARDIAN_HANDOVER_DELAY;
+                    let handover = GuardianHandover {
+                        incoming,
+                        outgoing,
+                        activates_at,
+                    };
+                    self.guardian_handovers.insert(proposal_id, &handover);
+                    self.env().emit_event(GuardianRotationScheduled {
+                        proposal_id,
+                        incoming,
+                        outgoing,
+                        activates_at,
+                    });
+                }
+                ProposalAction::ResolveEscrow { escrow, release } => {
+                    let mut record = self
+                        .escrows
+                        .get(escrow)
+                        .ok_or(GovernorError::EscrowNotFound)?;
+                    if record.arbiter.is_none() && !record.objected {
+                        return Err(GovernorError::InvalidParameter);
+                    }
+                    if let Some(dispute) = record.dispute.take() {
+                        self.escrow_disputes.remove(dispute);
+                    }
+                    if !release {
+                        return self.do_clawback(escrow);
+                    }
+                    record.attested = true;
+                    if record.objected {
+                        record.objected = false;
+                        record.disbursable_at = Some(self.now());
+                    }
+                    self.escrows.insert(escrow, &record);
+                }
+                ProposalAction::CloseGrant { grant, completed } => {
+                    let mut record = self.grant(grant)?;
+                    if record.status != GrantStatus::Open {
+                        return Err(GovernorError::GrantClosed);
+                    }
+                    record.status = match completed {
+                        true => GrantStatus::Completed,
+                        false => GrantStatus::Failed,
+                    };
+                    self.grants.insert(grant, &record);
+                    self.env().emit_event(GrantClosed {
+                        proposal_id: grant,
+                        status: record.status,
+                    });
+                }
+                ProposalAction::ClearSponsorStrikes(sponsor) => {
+                    self.sponsor_strikes.remove(sponsor)
+                }
+            }
+            Ok(())
+        }
+
+        /// The grant record of an executed proposal, open and without reports
+        /// until its recipient first reports. The recipient is the payee of a
+        /// milestone payout, or otherwise the proposal's `to` account.
+        fn grant(&self, proposal_id: ProposalId) -> Result<GrantRecord> {
+            if let Some(grant) = self.grants.get(proposal_id) {
+                return Ok(grant);
+            }
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            let status = self.proposal_status.get(proposal_id).unwrap_or_default();
+            if !status.has(STATUS_EXECUTED) {
+                return Err(GovernorError::GrantNotFound);
+            }
+            let recipient = self
+                .milestone_plans
+                .get(proposal_id)
+                .map_or(proposal.to, |plan| plan.recipient);
+
+            Ok(GrantRecord {
+                recipient,
+                reports: Vec::new(),
+                status: GrantStatus::Open,
+            })
+        }
+
+        /// Instantiates a token for `DeployToken`. When migrating, balances
+        /// already delegated in the old token keep their checkpoints until their
+        /// holders delegate again.
+        fn deploy_token(
+            &mut self,
+            proposal_id: ProposalId,
+            code_hash: Hash,
+            constructor: [u8; 4],
+            total_supply: Balance,
+            salt: &[u8],
+            migration_root: Option<Hash>,
+        ) -> Result<()> {
+            let created = build_create::<TokenRef>()
+                .code_hash(code_hash)
+                .gas_limit(0)
+                .endowment(0)
+                .exec_input(ExecutionInput::new(Selector::new(constructor)).push_arg(total_supply))
+                .salt_bytes(salt)
+                .returns::<TokenRef>()
+                .try_instantiate();
+            let token = match created {
+                Ok(Ok(token)) => token.account_id(),
+                _ => return Err(GovernorError::TokenDeploymentFailed),
+            };
+
+            if let Some(root) = migration_root {
+                self.register_airdrop(Some(token), root, total_supply)?;
+                self.config.governance_token = token;
+                self.refresh_token_info();
+                self.pin_governance_token();
+            }
+
+            self.env().emit_event(TokenDeployed {
+                proposal_id,
+                token,
+                total_supply,
+                migrated: migration_root.is_some(),
+            });
+
+            Ok(())
+        }
+
+        fn start_shutdown(
+            &mut self,
+            proposal_id: ProposalId,
+            successor: AccountId,
+            distribution_root: Option<Hash>,
+        ) -> Result<()> {
+            self.ensure_not_shut_down()?;
+            // Bonded funds would be left out of the hand-off or distribution.
+            if self.total_staked > 0 || self.total_unbonding > 0 {
+                return Err(GovernorError::TreasuryStaked);
+            }
+            let treasury = self.treasury_balance();
+            let airdrop_id = match distribution_root {
+                Some(root) if treasury > 0 => Some(self.register_airdrop(None, root, treasury)?),
+                Some(_) => None,
+                None => {
+                    self.env()
+                        .transfer(successor, treasury)
+                        .map_err(|_| GovernorError::TransferError)?;
+                    None
+                }
+            };
+
+            self.shutdown = Some(Shutdown {
+                successor,
+                at: self.now(),
+                airdrop_id,
+            });
+            self.env().emit_event(ShutdownStarted {
+                proposal_id,
+                successor,
+                treasury,
+                pro_rata: distribution_root.is_some(),
+            });
+
+            Ok(())
+        }
+
+        fn call_pool(&mut self, proposal_id: ProposalId, call: PoolCall) -> Result<()> {
+            let func_id = self
+                .config
+                .pool_staking_func_id
+                .ok_or(GovernorError::StakingUnavailable)?;
+            let staked = pool_staking(func_id, call);
+            let output = staked.then(|| staked.encode());
+            self.log_call(
+                proposal_id,
+                self.env().account_id(),
+                func_id.to_be_bytes(),
+                output,
+            );
+            if !staked {
+                return Err(GovernorError::StakingFailed);
+            }
+            Ok(())
+        }
+
+        fn emit_stake_changed(&self, proposal_id: ProposalId) {
+            self.env().emit_event(TreasuryStakeChanged {
+                proposal_id,
+                pool_id: self.staking_pool.unwrap_or_default(),
+                staked: self.total_staked,
+                unbonding: self.total_unbonding,
+            });
+        }
+
+        /// Tokens the governor holds for others: the unclaimed part of airdrops
+        /// of `token` and, for the governance token, voters' locks.
+        fn reserved_tokens(&self, token: AccountId) -> Balance {
+            let locked = if token == self.config.governance_token {
+                self.total_tokens_locked
+            } else {
+                0
+            };
+            locked.saturating_add(self.airdrop_reserved_tokens.get(token).unwrap_or(0))
+        }
+
+        fn release_airdrop_tokens(&mut self, token: AccountId, amount: Balance) {
+            let held = self.airdrop_reserved_tokens.get(token).unwrap_or(0);
+            let reserved = held - amount;
+            if reserved > 0 {
+                self.airdrop_reserved_tokens.insert(token, &reserved);
+            } else if held > 0 {
+                self.airdrop_reserved_tokens.remove(token);
+                self.airdrop_reserved_token_count -= 1;
+            }
+        }
+
+        /// Trades treasury tokens through the DEX router, checking the output
+        /// by the change in balance rather than trusting the router's report.
+        fn swap(
+            &mut self,
+            proposal_id: ProposalId,
+            token_in: AccountId,
+            token_out: AccountId,
+            amount_in: Balance,
+            min_amount_out: Balance,
+        ) -> Result<()> {
+            let router = self
+                .config
+                .dex_router
+                .ok_or(GovernorError::DexUnavailable)?;
+            self.ensure_code_intact(router)?;
+            self.ensure_code_intact(token_in)?;
+            self.ensure_code_intact(token_out)?;
+            let this = self.env().account_id();
+            let available = PSP22Ref::balance_of(&token_in, this)
+                .saturating_sub(self.reserved_tokens(token_in));
+            if available < amount_in {
+                return Err(GovernorError::InsufficientTreasuryBalance {
+                    available,
+                    required: amount_in,
+                });
+            }
+
+            let balance_before = PSP22Ref::balance_of(&token_out, this);
+            self.approve_tokens(proposal_id, token_in, router, amount_in)?;
+            let input = ExecutionInput::new(Selector::new(dex::SWAP_EXACT_IN))
+                .push_arg(token_in)
+                .push_arg(token_out)
+                .push_arg(amount_in)
+                .push_arg(min_amount_out)
+                .push_arg(this);
+            let swapped = build_call::<ink::env::DefaultEnvironment>()
+                .call(router)
+                .gas_limit(0)
+                .exec_input(input)
+                .returns::<u128>()
+                .try_invoke();
+            let output = match &swapped {
+                Ok(Ok(amount_out)) => Some(amount_out.encode()),
+                _ => None,
+            };
+            self.log_call(proposal_id, router, dex::SWAP_EXACT_IN, output);
+            self.approve_tokens(proposal_id, token_in, router, 0)?;
+            if !matches!(swapped, Ok(Ok(_))) {
+                return Err(GovernorError::SwapFailed);
+            }
+
+            let amount_out = PSP22Ref::balance_of(&token_out, this).saturating_sub(balance_before);
+            if amount_out < min_amount_out {
+                return Err(GovernorError::SwapFailed);
+            }
+            self.env().emit_event(TokensSwapped {
+                proposal_id,
+                token_in,
+                token_out,
+                amount_in,
+                amount_out,
+            });
+
+            Ok(())
+        }
+
+        /// Sends treasury tokens while executing a proposal, logging the call.
+        fn transfer_tokens(
+            &mut self,
+            proposal_id: ProposalId,
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let transferred = PSP22Ref::transfer(&token, to, amount, Vec::new());
+            let output = transferred.as_ref().ok().map(|result| result.encode());
+            self.log_call(proposal_id, token, token::TRANSFER, output);
+            transferred.map_err(|_| GovernorError::TokenTransferFailed)
+        }
+
+        fn approve_tokens(
+            &mut self,
+            proposal_id: ProposalId,
+            token: AccountId,
+            spender: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let approved = PSP22Ref::approve(&token, spender, amount);
+            let output = approved.as_ref().ok().map(|result| result.encode());
+            self.log_call(proposal_id, token, token::APPROVE, output);
+            approved.map_err(|_| GovernorError::SwapFailed)
+        }
+
+        /// Appends a call to the proposal's execution log. `output` is the encoded
+        /// return value, `None` if the call failed.
+        fn log_call(
+            &mut self,
+            proposal_id: ProposalId,
+            callee: AccountId,
+            selector: [u8; 4],
+            output: Option<Vec<u8>>,
+        ) {
+            let success = output.is_some();
+            let mut output = output.unwrap_or_default();
+            output.truncate(MAX_CALL_OUTPUT);
+            let mut log = self.execution_logs.get(proposal_id).unwrap_or_default();
+            log.push(CallRecord {
+                callee,
+                selector,
+                success,
+                output,
+            });
+            self.execution_logs.insert(proposal_id, &log);
+        }
+
+        /// Moves the treasury and the governance tokens not reserved for airdrops
+        /// or locks to `new_governor` once it accepts the hand-off, passing it
+        /// the proposals still open.
+        fn migrate_to(&mut self, proposal_id: ProposalId, new_governor: AccountId) -> Result<()> {
+            let open_proposals: Vec<ProposalId> = (0..self.open_proposal_count)
+                .filter_map(|slot| self.open_proposals.get(slot))
+                .collect();
+
+            let token = self.config.governance_token;
+            self.ensure_code_intact(token)?;
+            let input = ExecutionInput::new(Selector::new(migration::ACCEPT_MIGRATION))
+                .push_arg(token)
+                .push_arg(&open_proposals);
+            let accepted = build_call::<ink::env::DefaultEnvironment>()
+                .call(new_governor)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(input)
+                .returns::<bool>()
+                .try_invoke();
+            let output = match &accepted {
+                Ok(Ok(accepted)) => Some(accepted.encode()),
+                _ => None,
+            };
+            self.log_call(
+                proposal_id,
+                new_governor,
+                migration::ACCEPT_MIGRATION,
+                output,
+            );
+            if !matches!(accepted, Ok(Ok(true))) {
+                return Err(GovernorError::MigrationRejected);
+            }
+
+            let tokens = PSP22Ref::balance_of(&token, self.env().account_id())
+                .saturating_sub(self.reserved_tokens(token));
+            if tokens > 0 {
+                self.transfer_tokens(proposal_id, token, new_governor, tokens)?;
+            }
+
+            self.start_shutdown(proposal_id, new_governor, None)?;
+            self.env().emit_event(GovernorMigrated {
+                proposal_id,
+                new_governor,
+                open_proposals,
+                tokens,
+            });
+
+            Ok(())
+        }
+
+        fn ensure_not_shut_down(&self) -> Result<()> {
+            if self.shutdown.is_some() {
+                return Err(GovernorError::GovernorShutDown);
+            }
+            Ok(())
+        }
+
+        fn register_airdrop(
+            &mut self,
+            token: Option<AccountId>,
+            root: Hash,
+            budget: Balance,
+        ) -> Result<u32> {
+            match token {
+                Some(token) => {
+                    let reserved = self.airdrop_reserved_tokens.get(token).unwrap_or(0);
+                    if reserved == 0 {
+                        self.airdrop_reserved_token_count += 1;
+                    }
+                    self.airdrop_reserved_tokens
+                        .insert(token, &(reserved + budget));
+                }
+                None => {
+                    self.ensure_treasury_covers(budget)?;
+                    self.total_airdrop_reserved += budget;
+                }
+            }
+
+            let airdrop_id = self.next_airdrop_id;
+            self.next_airdrop_id += 1;
+            let airdrop = Airdrop {
+                token,
+                root,
+                budget,
+                claimed: 0,
+            };
+            self.airdrops.insert(airdrop_id, &airdrop);
+
+            Ok(airdrop_id)
+        }
+
+        fn close_airdrop(&mut self, airdrop_id: u32) -> Result<()> {
+            let airdrop = self
+                .airdrops
+                .get(airdrop_id)
+                .ok_or(GovernorError::AirdropNotFound)?;
+
+            let unclaimed = airdrop.budget - airdrop.claimed;
+            match airdrop.token {
+                Some(token) => self.release_airdrop_tokens(token, unclaimed),
+                None => self.total_airdrop_reserved -= unclaimed,
+            }
+            self.airdrops.remove(airdrop_id);
+
+            self.env().emit_event(AirdropClosed {
+                airdrop_id,
+                unclaimed,
+            });
+
+            Ok(())
+        }
+
+        fn escrow_payout(
+            &mut self,
+            proposal_id: ProposalId,
+            recipient: AccountId,
+            amount: Balance,
+        ) {
+            let arbiter = self
+                .proposal_actions
+                .get(proposal_id)
+                .unwrap_or_default()
+                .into_iter()
+                .find_map(|action| match action {
+                    ProposalAction::Arbitrated(arbiter) => Some(arbiter),
+                    _ => None,
+                });
+            let escrow = Escrow {
+                recipient,
+                amount,
+                created_at: self.now(),
+                arbiter,
+                attested: false,
+                dispute: None,
+                two_phase: matches!(
+                    self.config.disbursement_threshold,
+                    Some(threshold) if amount > threshold
+                ),
+                disbursable_at: None,
+                objected: false,
+            };
+            self.escrows.insert(proposal_id, &escrow);
+            self.total_escrowed += amount;
+            self.env().emit_event(PayoutEscrowed {
+                proposal_id,
+                recipient,
+                amount,
+            });
+        }
+
+        fn treasury_share(&self, bps: u16, cap: Balance) -> Balance {
+            let share = self.treasury_balance().saturating_mul(bps as Balance)
+                / MAX_BASIS_POINTS as Balance;
+            share.min(cap)
+        }
+
+        /// Applies `WholeTokenAmounts` to a proposal's amounts and removes it.
+        fn to_smallest_units(
+            &self,
+            amount: Balance,
+            actions: Vec<ProposalAction>,
+        ) -> Result<(Balance, Vec<ProposalAction>)> {
+            let native = self.config.native_decimals;
+            let scale = |value: Balance, decimals: u8| {
+                token::to_smallest_unit(value, decimals).ok_or(GovernorError::InvalidParameter)
+            };
+
+            let mut converted = Vec::with_capacity(actions.len());
+            for action in actions {
+                let action = match action {
+                    ProposalAction::WholeTokenAmounts => continue,
+                    ProposalAction::Swap {
+                        token_in,
+                        token_out,
+                        amount_in,
+                        min_amount_out,
+                    } => ProposalAction::Swap {
+                        token_in,
+                        token_out,
+                        amount_in: scale(amount_in, self.token_decimals(token_in)?)?,
+                        min_amount_out: scale(min_amount_out, self.token_decimals(token_out)?)?,
+                    },
+                    ProposalAction::RegisterAirdrop {
+                        token,
+                        root,
+                        budget,
+                    } => {
+                        let decimals = match token {
+                            Some(token) => self.token_decimals(token)?,
+                            None => native,
+                        };
+                        ProposalAction::RegisterAirdrop {
+                            token,
+                            root,
+                            budget: scale(budget, decimals)?,
+                        }
+                    }
+                    ProposalAction::Stake { pool_id, amount } => ProposalAction::Stake {
+                        pool_id,
+                        amount: scale(amount, native)?,
+                    },
+                    ProposalAction::Unstake(amount) => {
+                        ProposalAction::Unstake(scale(amount, native)?)
+                    }
+                    ProposalAction::MilestonePayout {
+                        recipient,
+                        tranches,
+                    } => ProposalAction::MilestonePayout {
+                        recipient,
+                        tranches: tranches
+                            .into_iter()
+                            .map(|tranche| scale(tranche, native))
+                            .collect::<Result<_>>()?,
+                    },
+                    ProposalAction::TreasuryShare { bps, cap } => ProposalAction::TreasuryShare {
+                        bps,
+                        cap: scale(cap, native)?,
+                    },
+                    // `usd` is in the price feed's units, which aren't whole tokens.
+                    ProposalAction::UsdPayout { usd, max_amount } => ProposalAction::UsdPayout {
+                        usd,
+                        max_amount: scale(max_amount, native)?,
+                    },
+                    // The decimals of a token that doesn't exist yet are unknown.
+                    ProposalAction::DeployToken { .. } => {
+                        return Err(GovernorError::InvalidParameter)
+                    }
+                    action => action,
+                };
+                converted.push(action);
+            }
+
+            Ok((scale(amount, native)?, converted))
+        }
+
+        fn token_decimals(&self, token: AccountId) -> Result<u8> {
+            if token == self.config.governance_token {
+                if let Some(decimals) = self.governance_token_info.decimals {
+                    return Ok(decimals);
+                }
+            }
+            match build_call::<ink::env::DefaultEnvironment>()
+                .call(token)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(ExecutionInput::new(Selector::new(token::TOKEN_DECIMALS)))
+                .returns::<u8>()
+                .try_invoke()
+            {
+                Ok(Ok(decimals)) => Ok(decimals),
+                _ => Err(GovernorError::TokenMetadataUnavailable),
+            }
+        }
+
+        /// Pins the governance token to its current code, if it's a contract.
+        fn pin_governance_token(&mut self) {
+            let token = self.config.governance_token;
+            if let Ok(code_hash) = self.env().code_hash(&token) {
+                self.pinned_code.insert(token, &code_hash);
+            }
+        }
+
+        /// Code hash `contract` is pinned to, as a dependency or a strategy
+        /// adapter.
+        fn expected_code(&self, contract: AccountId) -> Option<Hash> {
+            self.pinned_code
+                .get(contract)
+                .or_else(|| self.strategy_adapters.get(contract))
+        }
+
+        /// Whether `contract` is unpinned or still runs the code it was pinned to.
+        fn code_intact(&self, contract: AccountId) -> bool {
+            match self.expected_code(contract) {
+                Some(expected) => self.env().code_hash(&contract).ok() == Some(expected),
+                None => true,
+            }
+        }
+
+        fn ensure_code_intact(&self, contract: AccountId) -> Result<()> {
+            if !self.code_intact(contract) {
+                return Err(GovernorError::DependencyCodeChanged);
+            }
+            Ok(())
+        }
+
+        /// Caches the governance token's `PSP22Metadata`. Accounts without code
+        /// aren't called, so nothing is cached for them.
+        fn refresh_token_info(&mut self) {
+            let token = self.config.governance_token;
+            if !self.env().is_contract(&token) {
+                self.governance_token_info = TokenInfo::default();
+                return;
+            }
+            let gas_limit = self.config.hook_gas_limit;
+            let read = |selector: [u8; 4]| {
+                build_call::<ink::env::DefaultEnvironment>()
+                    .call(token)
+                    .gas_limit(gas_limit)
+                    .exec_input(ExecutionInput::new(Selector::new(selector)))
+            };
+            let name = match read(token::TOKEN_NAME)
+                .returns::<Option<String>>()
+                .try_invoke()
+            {
+                Ok(Ok(name)) => name,
+                _ => None,
+            };
+            let symbol = match read(token::TOKEN_SYMBOL)
+                .returns::<Option<String>>()
+                .try_invoke()
+            {
+                Ok(Ok(symbol)) => symbol,
+                _ => None,
+            };
+            let decimals = match read(token::TOKEN_DECIMALS).returns::<u8>().try_invoke() {
+                Ok(Ok(decimals)) => Some(decimals),
+                _ => None,
+            };
+            self.governance_token_info = TokenInfo {
+                name,
+                symbol,
+                decimals,
+            };
+        }
+
+        /// Converts `usd` into the native token at the feed's current price,
+        /// rejecting prices older than `max_price_age`.
+        fn usd_to_native(&self, usd: Balance, max_amount: Balance) -> Result<Balance> {
+            let feed = self
+                .config
+                .price_feed
+                .ok_or(GovernorError::PriceUnavailable)?;
+            self.ensure_code_intact(feed.oracle)?;
+            let (price, updated_at) = match build_call::<ink::env::DefaultEnvironment>()
+                .call(feed.oracle)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(ExecutionInput::new(Selector::new(oracle::LATEST_PRICE)))
+                .returns::<Option<(u128, u64)>>()
+                .try_invoke()
+            {
+                Ok(Ok(Some((price, updated_at)))) if price > 0 => (price, updated_at),
+                _ => return Err(GovernorError::PriceUnavailable),
+            };
+            let now = self.now();
+            if updated_at > now || now - updated_at > self.config.max_price_age {
+                return Err(GovernorError::PriceStale);
+            }
+            // An amount too large for a `Balance` is above any cap.
+            Ok(
+                oracle::native_amount(usd, price, self.config.native_decimals)
+                    .map_or(max_amount, |amount| amount.min(max_amount)),
+            )
+        }
+
+        fn do_clawback(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let escrow = self
+                .escrows
+                .get(proposal_id)
+                .ok_or(GovernorError::EscrowNotFound)?;
+
+            self.escrows.remove(proposal_id);
+            self.total_escrowed -= escrow.amount;
+
+            self.env().emit_event(PayoutClawedBack {
+                proposal_id,
+                amount: escrow.amount,
+            });
+
+            Ok(())
+        }
+
+        fn do_release_tranche(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let mut plan = self
+                .milestone_plans
+                .get(proposal_id)
+                .ok_or(GovernorError::MilestonePlanNotFound)?;
+
+            let index = plan.released;
+            let amount = match plan.tranches.get(index as usize) {
+                Some(&amount) if !plan.canceled => amount,
+                _ => return Err(GovernorError::NoTrancheRemaining),
+            };
+            self.ensure_treasury_covers(amount)?;
+
+            plan.released += 1;
+            self.milestone_plans.insert(proposal_id, &plan);
+            self.env()
+                .transfer(plan.recipient, amount)
+                .map_err(|_| GovernorError::TransferError)?;
+
+            self.env().emit_event(TrancheReleased {
+                proposal_id,
+                index,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        fn do_cancel_milestones(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let mut plan = self
+                .milestone_plans
+                .get(proposal_id)
+                .ok_or(GovernorError::MilestonePlanNotFound)?;
+
+            if plan.canceled || plan.released as usize >= plan.tranches.len() {
+                return Err(GovernorError::NoTrancheRemaining);
+            }
+
+            plan.canceled = true;
+            self.milestone_plans.insert(proposal_id, &plan);
+
+            let unreleased = plan.tranches[plan.released as usize..].iter().sum();
+            self.env().emit_event(MilestonesCanceled {
+                proposal_id,
+                unreleased,
+            });
+
+            Ok(())
+        }
+
+        fn apply_parameter_change(&mut self, change: &ParameterChange) {
+            match *change {
+                ParameterChange::ValidateTreasuryOnPropose(value) => {
+                    self.config.validate_treasury_on_propose = value
+                }
+                ParameterChange::Quorum(value) => self.config.quorum = value,
+                ParameterChange::ApprovalThreshold(value) => self.config.approval_threshold = value,
+                ParameterChange::MaxVoteDuration(value) => self.config.max_vote_duration = value,
+                ParameterChange::Timelock(value) => self.config.timelock = value,
+                ParameterChange::ExecutorGracePeriod(value) => {
+                    self.config.executor_grace_period = value
+                }
+                ParameterChange::MaxActions(value) => self.config.max_actions = value,
+                ParameterChange::MaxPayloadSize(value) => self.config.max_payload_size = value,
+                ParameterChange::CosignThreshold(value) => self.config.cosign_threshold = value,
+                ParameterChange::RequiredCosignatures(value) => {
+                    self.config.required_cosignatures = value
+                }
+                ParameterChange::ClaimPeriod(value) => self.config.claim_period = value,
+                ParameterChange::ProposalFee(value) => self.config.proposal_fee = value,
+                ParameterChange::ProposalBond(value) => self.config.proposal_bond = value,
+                ParameterChange::SlashBounty(value) => self.config.slash_bounty = value,
+                ParameterChange::HookGasLimit(value) => self.config.hook_gas_limit = value,
+                ParameterChange::StorageDepositPerByte(value) => {
+                    self.config.storage_deposit_per_byte = value
+                }
+                ParameterChange::MaxActiveProposals(value) => {
+                    self.config.max_active_proposals = value
+                }
+                ParameterChange::ResubmissionCooldown(value) => {
+                    self.config.resubmission_cooldown = value
+                }
+                ParameterChange::BallotDecryptionFuncId(value) => {
+                    self.config.ballot_decryption_func_id = value
+                }
+                ParameterChange::CandidacyBond(value) => self.config.candidacy_bond = value,
+                ParameterChange::EmergencyCap(value) => self.config.emergency_cap = value,
+                ParameterChange::EmergencyBond(value) => self.config.emergency_bond = value,
+                ParameterChange::MaxPriceAge(value) => self.config.max_price_age = value,
+                ParameterChange::ExecutionWindow(value) => self.config.execution_window = value,
+                ParameterChange::DiscussionPeriod(value) => self.config.discussion_period = value,
+                ParameterChange::MaxBondDiscount(value) => self.config.max_bond_discount = value,
+                ParameterChange::ReputationMinExecuted(value) => {
+                    self.config.reputation_min_executed = value
+                }
+                ParameterChange::DelegationDecayPeriod(value) => {
+                    self.config.delegation_decay_period = value
+                }
+                ParameterChange::MinVoteWeight(value) => self.config.min_vote_weight = value,
+                ParameterChange::MinHoldingPeriod(value) => self.config.min_holding_period = value,
+                ParameterChange::RequiredSponsors(value) => self.config.required_sponsors = value,
+                ParameterChange::MinSponsorWeight(value) => self.config.min_sponsor_weight = value,
+                ParameterChange::ReminderWindow(value) => self.config.reminder_window = value,
+                ParameterChange::DisbursementThreshold(value) => {
+                    self.config.disbursement_threshold = value
+                }
+                ParameterChange::ObjectionWindow(value) => self.config.objection_window = value,
+                ParameterChange::WatchdogBond(value) => self.config.watchdog_bond = value,
+                ParameterChange::GuardianPowersUntil(value) => {
+                    self.config.guardian_powers_until = value
+                }
+                ParameterChange::ContractVoters(value) => self.config.contract_voters = value,
+                ParameterChange::PoolStakingFuncId(value) => {
+                    self.config.pool_staking_func_id = value
+                }
+            }
+        }
+
+        fn validate_parameter_change(&self, change: &ParameterChange) -> Result<()> {
+            let valid = match *change {
+                ParameterChange::ValidateTreasuryOnPropose(_) | ParameterChange::Quorum(_) => true,
+                ParameterChange::ApprovalThreshold(value) => value < MAX_BASIS_POINTS,
+                ParameterChange::MaxVoteDuration(value) => {
+                    value > 0 && value <= MAX_DELAY / ONE_MINUTE
+                }
+                ParameterChange::Timelock(value)
+                | ParameterChange::ExecutorGracePeriod(value)
+                | ParameterChange::ResubmissionCooldown(value)
+                | ParameterChange::DiscussionPeriod(value) => value <= MAX_DELAY,
+                ParameterChange::MaxActions(value) => value > 0 && value <= MAX_ACTIONS_LIMIT,
+                ParameterChange::MaxPayloadSize(value) => {
+                    value > 0 && value <= MAX_PAYLOAD_SIZE_LIMIT
+                }
+                ParameterChange::CosignThreshold(_)
+                | ParameterChange::BallotDecryptionFuncId(_) => true,
+                ParameterChange::RequiredCosignatures(value) => value <= MAX_COSIGNERS,
+                // An objection must be raised before the payout can be reclaimed.
+                ParameterChange::ClaimPeriod(value) => {
+                    value <= MAX_CLAIM_PERIOD
+                        && (value == 0 || self.config.objection_window < value)
+                }
+                ParameterChange::ExecutionWindow(value) => value <= MAX_CLAIM_PERIOD,
+                ParameterChange::ProposalFee(_)
+                | ParameterChange::ProposalBond(_)
+                | ParameterChange::StorageDepositPerByte(_)
+                | ParameterChange::CandidacyBond(_)
+                | ParameterChange::EmergencyCap(_)
+                | ParameterChange::EmergencyBond(_)
+                | ParameterChange::MinVoteWeight(_)
+                | ParameterChange::MinSponsorWeight(_) => true,
+                ParameterChange::RequiredSponsors(value) => value <= MAX_SPONSORS,
+                ParameterChange::SlashBounty(value) | ParameterChange::MaxBondDiscount(value) => {
+                    value <= MAX_BASIS_POINTS
+                }
+                ParameterChange::ReputationMinExecuted(_) => true,
+                ParameterChange::DelegationDecayPeriod(value) => value == 0 || value >= ONE_DAY,
+                ParameterChange::HookGasLimit(value) => value > 0 && value <= MAX_HOOK_GAS_LIMIT,
+                ParameterChange::MaxActiveProposals(value) => {
+                    value > 0 && value <= MAX_ACTIVE_PROPOSALS_LIMIT
+                }
+                ParameterChange::MaxPriceAge(value) => value > 0 && value <= MAX_DELAY,
+                ParameterChange::MinHoldingPeriod(value) => value <= MAX_DELAY,
+                ParameterChange::ReminderWindow(value) => value <= MAX_DELAY,
+                ParameterChange::ObjectionWindow(value) => {
+                    let claim_period = self.config.claim_period;
+                    value <= MAX_DELAY && (claim_period == 0 || value < claim_period)
+                }
+                ParameterChange::DisbursementThreshold(_) | ParameterChange::WatchdogBond(_) => {
+                    true
+                }
+                ParameterChange::GuardianPowersUntil(_) | ParameterChange::ContractVoters(_) => {
+                    true
+                }
+                // Funds still in the pool could only be withdrawn through it.
+                ParameterChange::PoolStakingFuncId(value) => {
+                    value.is_some() || (self.total_staked == 0 && self.total_unbonding == 0)
+                }
+            };
+            if !valid {
+                return Err(GovernorError::InvalidParameter);
+            }
+            Ok(())
+        }
+
+        /// Takes the proposal fee from `proposer` and burns it. For tokens without
+        /// the burnable extension the fee stays in the treasury instead.
+        fn burn_proposal_fee(
+            &mut self,
+            proposal_id: ProposalId,
+            proposer: AccountId,
+        ) -> Result<()> {
+            let token = self.config.governance_token;
+            let this = self.env().account_id();
+            let amount = self.config.proposal_fee;
+            self.ensure_code_intact(token)?;
+
+            PSP22Ref::transfer_from(&token, proposer, this, amount, Vec::new())
+                .map_err(|_| GovernorError::FeePaymentFailed)?;
+            let burned = PSP22BurnableRef::burn(&token, this, amount).is_ok();
+
+            self.env().emit_event(ProposalFeeBurned {
+                proposal_id,
+                proposer,
+                amount,
+                burned,
+            });
+
+            Ok(())
+        }
+
+        fn ensure_role(&self, role: RoleType) -> Result<()> {
+            self.ensure_account_role(role, self.env().caller())
+        }
+
+        fn ensure_account_role(&self, role: RoleType, account: AccountId) -> Result<()> {
+            if !self.has_role(role, account) {
+                return Err(GovernorError::MissingRole);
+            }
+            if (role == GUARDIAN_ROLE || role == COUNCIL_ROLE) && !self.guardian_powers_active() {
+                return Err(GovernorError::GuardianPowersExpired);
+            }
+            Ok(())
+        }
+
+        /// Tokens sent to a proposal's for or against address count as votes on top
+        /// of the weight cast through `vote`. Native balances of other accounts
+        /// can't be read, so native-weighted governors only count `vote`.
+        fn add_deposit_weights(
+            &self,
+            proposal: &Proposal,
+            status: &mut ProposalStatus,
+            cache: &mut WeightCache,
+        ) {
+            if self.config.weight_source != WeightSource::Psp22 {
+                return;
+            }
+            let weight_for = cache.weight_of(proposal.for_address, || {
+                self.undelegated_balance(proposal.for_address)
+            });
+            let weight_against = cache.weight_of(proposal.against_address, || {
+                self.undelegated_balance(proposal.against_address)
+            });
+            status.for_votes = status.for_votes.saturating_add(weight_for);
+            status.against_votes = status.against_votes.saturating_add(weight_against);
+        }
+
+        /// Live governance token balance of `account` less what it delegated away,
+        /// which already counts for its delegatee.
+        fn undelegated_balance(&self, account: AccountId) -> Balance {
+            let delegated = self
+                .delegations
+                .get(account)
+                .map_or(0, |delegation| delegation.weight);
+            self.token_weight(self.config.governance_token, account)
+                .saturating_sub(delegated)
+        }
+
+        /// LP weight `account` had recorded as of `snapshot` and still holds. LP
+        /// tokens moved after their holder's checkpoint count for neither side
+        /// until the new holder records them, so they can't vote twice.
+        fn lp_weight_at(&self, account: AccountId, snapshot: Timestamp) -> Balance {
+            if self.lp_pools.is_empty() {
+                return 0;
+            }
+            let recorded = weight::held_before(
+                &self.lp_checkpoints.get(account).unwrap_or_default(),
+                snapshot,
+                self.config.min_holding_period,
+            );
+            if recorded == 0 {
+                return 0;
+            }
+            recorded.min(self.lp_weight(account))
+        }
+
+        /// Governance tokens backing `account`'s registered LP positions. A pool
+        /// whose LP token or adapter fails contributes nothing.
+        fn lp_weight(&self, account: AccountId) -> Balance {
+            let token = self.config.governance_token;
+            self.lp_pools
+                .iter()
+                .map(|pool| {
+                    let liquidity = self.token_weight(pool.lp_token, account);
+                    if liquidity == 0 {
+                        return 0;
+                    }
+                    let input = ExecutionInput::new(Selector::new(pair::UNDERLYING))
+                        .push_arg(pool.lp_token)
+                        .push_arg(token)
+                        .push_arg(liquidity);
+                    let underlying = build_call::<ink::env::DefaultEnvironment>()
+                        .call(pool.adapter)
+                        .gas_limit(self.config.hook_gas_limit)
+                        .exec_input(input)
+                        .returns::<Balance>()
+                        .try_invoke();
+                    match underlying {
+                        Ok(Ok(underlying)) => underlying,
+                        _ => self.weight_source_failed(pool.adapter, account),
+                    }
+                })
+                .fold(0, Balance::saturating_add)
+        }
+
+        /// `account`'s balance of `token` as voting weight, read within
+        /// `hook_gas_limit` so a trapping or gas-hungry token can't block
+        /// voting or execution.
+        fn token_weight(&self, token: AccountId, account: AccountId) -> Balance {
+            if !self.code_intact(token) {
+                return self.weight_source_failed(token, account);
+            }
+            let balance = build_call::<ink::env::DefaultEnvironment>()
+                .call(token)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(ExecutionInput::new(Selector::new(token::BALANCE_OF)).push_arg(account))
+                .returns::<Balance>()
+                .try_invoke();
+            match balance {
+                Ok(Ok(balance)) => balance,
+                _ => self.weight_source_failed(token, account),
+            }
+        }
+
+        /// Weight a registered adapter reports, or zero if it isn't registered,
+        /// runs code other than what it was pinned to, or the call fails.
+        fn adapter_weight(
+            &self,
+            adapter: AccountId,
+            account: AccountId,
+            snapshot: Timestamp,
+        ) -> Balance {
+            if !self.strategy_adapters.contains(adapter) {
+                return 0;
+            }
+            if !self.code_intact(adapter) {
+                return self.weight_source_failed(adapter, account);
+            }
+            let input = ExecutionInput::new(Selector::new(strategy::WEIGHT_AT))
+                .push_arg(account)
+                .push_arg(snapshot);
+            let weight = build_call::<ink::env::DefaultEnvironment>()
+                .call(adapter)
+                .gas_limit(self.config.hook_gas_limit)
+                .exec_input(input)
+                .returns::<Balance>()
+                .try_invoke();
+            match weight {
+                Ok(Ok(weight)) => weight,
+                _ => self.weight_source_failed(adapter, account),
+            }
+        }
+
+        fn weight_source_failed(&self, callee: AccountId, account: AccountId) -> Balance {
+            self.env()
+                .emit_event(WeightSourceFailed { callee, account });
+            0
+        }
+
+        fn next_proposal_id(&mut self) -> ProposalId {
+            let id = self.next_proposal_id;
+            self.next_proposal_id += 1;
+            self.open_proposals.insert(self.open_proposal_count, &id);
+            self.open_proposal_slots
+                .insert(id, &self.open_proposal_count);
+            self.open_proposal_count += 1;
+            id
+        }
+
+        /// Drops a finalized or canceled proposal from the open proposals, moving
+        /// the last one into its slot.
+        fn close_proposal(&mut self, proposal_id: ProposalId) {
+            let slot = match self.open_proposal_slots.get(proposal_id) {
+                Some(slot) => slot,
+                None => return,
+            };
+            self.open_proposal_slots.remove(proposal_id);
+            self.open_proposal_count -= 1;
+            let last = self.open_proposal_count;
+            if slot != last {
+                if let Some(moved) = self.open_proposals.get(last) {
+                    self.open_proposals.insert(slot, &moved);
+                    self.open_proposal_slots.insert(moved, &slot);
+                }
+            }
+            self.open_proposals.remove(last);
+        }
+
+        /// Category numbers start at 1 so the first treasury proposal is `TREASURY-1`.
+        fn next_category_number(&mut self, category: &str) -> u32 {
+            let number = self.category_counters.get(category).unwrap_or(0) + 1;
+            self.category_counters.insert(category, &number);
+            number
+        }
+
+        fn is_valid_category(category: &str) -> bool {
+            !category.is_empty()
+                && category.len() <= MAX_CATEGORY_LENGTH
+                && category
+                    .bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+        }
+    }
+
+    /// Arguments of `propose` with working defaults, so unit tests and the e2e
+    /// bench only spell out what they care about.
+    #[cfg(test)]
+    struct ProposalBuilder {
+        request: ProposalRequest,
+    }
+
+    #[cfg(test)]
+    impl ProposalBuilder {
+        fn new() -> Self {
+            Self {
+                request: ProposalRequest {
+                    for_address: AccountId::from([0x02; 32]),
+                    against_address: AccountId::from([0x03; 32]),
+                    to: AccountId::from([0x05; 32]),
+                    title: "Title".to_string(),
+                    description: "Description".to_string(),
+                    category: "TREASURY".to_string(),
+                    amount: 1,
+                    duration: 1,
+                    actions: Vec::new(),
+                },
+            }
+        }
+
+        fn to(mut self, to: AccountId) -> Self {
+            self.request.to = to;
+            self
+        }
+
+        fn title(mut self, title: &str) -> Self {
+            self.request.title = title.to_string();
+            self
+        }
+
+        fn category(mut self, category: &str) -> Self {
+            self.request.category = category.to_string();
+            self
+        }
+
+        fn amount(mut self, amount: Balance) -> Self {
+            self.request.amount = amount;
+            self
+        }
+
+        fn duration(mut self, duration: u64) -> Self {
+            self.request.duration = duration;
+            self
+        }
+
+        fn action(mut self, action: ProposalAction) -> Self {
+            self.request.actions.push(action);
+            self
+        }
+
+        fn build(self) -> ProposalRequest {
+            self.request
+        }
+
+        fn propose(self, governor: &mut VotingContract) -> Result<()> {
+            let request = self.build();
+            governor.propose(
+                request.for_address,
+                request.against_address,
+                request.to,
+                request.title,
+                request.description,
+                request.category,
+                request.amount,
+                request.duration,
+                request.actions,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Environment = ink::env::DefaultEnvironment;
+
+        fn test_config() -> GovernorConfig {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            GovernorConfig {
+                weight_source: WeightSource::Native,
+                quorum: 10,
+                timelock: 0,
+                executor_grace_period: 0,
+                ..GovernorConfig::default_config(accounts.django)
+            }
+        }
+
+        fn propose(governor: &mut VotingContract) {
+            ProposalBuilder::new().propose(governor).unwrap();
+        }
+
+        fn change_parameters(governor: &mut VotingContract) {
+            governor.apply_parameter_change(&ParameterChange::Quorum(1_000));
+            governor.apply_parameter_change(&ParameterChange::ApprovalThreshold(9_000));
+            governor.apply_parameter_change(&ParameterChange::Timelock(MAX_DELAY));
+            governor.apply_parameter_change(&ParameterChange::ExecutorGracePeriod(MAX_DELAY));
+        }
+
+        #[ink::test]
+        fn parameter_changes_do_not_affect_in_flight_proposals() {
+            let mut governor = VotingContract::new(test_config());
+            propose(&mut governor);
+
+            change_parameters(&mut governor);
+
+            let proposal = governor.get_proposal(0).unwrap();
+            assert_eq!(proposal.quorum, 10);
+            assert_eq!(proposal.approval_threshold, 5_000);
+            assert_eq!(proposal.timelock, 0);
+            assert_eq!(proposal.executor_grace_period, 0);
+
+            // Once voting ends the proposal is only held up by its own quorum, not
+            // by the timelock set after it was created.
+            ink::env::test::set_block_timestamp::<Environment>(proposal.vote_end);
+            assert_eq!(
+                governor.simulate_execute(0),
+                Err(GovernorError::QuorumNotReached {
+                    counted: 0,
+                    quorum: 10,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn new_proposals_use_changed_parameters() {
+            let mut governor = VotingContract::new(test_config());
+
+            change_parameters(&mut governor);
+            propose(&mut governor);
+
+            let proposal = governor.get_proposal(0).unwrap();
+            assert_eq!(proposal.quorum, 1_000);
+            assert_eq!(proposal.approval_threshold, 9_000);
+            assert_eq!(proposal.timelock, MAX_DELAY);
+            assert_eq!(proposal.executor_grace_period, MAX_DELAY);
+
+            ink::env::test::set_block_timestamp::<Environment>(proposal.vote_end);
+            assert_eq!(
+                governor.simulate_execute(0),
+                Err(GovernorError::TimelockNotElapsed {
+                    executable_at: proposal.vote_end + MAX_DELAY,
+                    now: proposal.vote_end,
+                })
+            );
+        }
+
+        /// Xorshift generator, so the random call sequences below are the same on
+        /// every run and a failing seed can be replayed.
+        struct Rng(u64);
+
+        impl Rng {
+            fn below(&mut self, bound: u64) -> u64 {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                self.0 % bound
+            }
+        }
+
+        const TREASURY: Balance = 1_000;
+
+        /// Applies one random call, ignoring its result, as a random account at a
+        /// random later time.
+        fn random_step(governor: &mut VotingContract, rng: &mut Rng, now: &mut Timestamp) {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            let voters = [accounts.bob, accounts.charlie, accounts.django];
+            *now += rng.below(3) * ONE_MINUTE;
+            ink::env::test::set_block_timestamp::<Environment>(*now);
+            ink::env::test::set_caller::<Environment>(accounts.alice);
+
+            let proposal_id = rng.below(governor.next_proposal_id as u64 + 1) as ProposalId;
+            let _ = match rng.below(6) {
+                0 => ProposalBuilder::new()
+                    .amount(1 + rng.below(TREASURY as u64) as Balance)
+                    .duration(1 + rng.below(4))
+                    .propose(governor),
+                1 => {
+                    let vote = match rng.below(3) {
+                        0 => VoteType::For,
+                        1 => VoteType::Against,
+                        _ => VoteType::Abstain,
+                    };
+                    let voter = voters[rng.below(voters.len() as u64) as usize];
+                    ink::env::test::set_caller::<Environment>(voter);
+                    governor.vote(proposal_id, vote)
+                }
+                2 => governor.finalize(proposal_id),
+                3 => governor.execute(proposal_id),
+                4 => governor.activate(proposal_id),
+                _ => governor.cancel(proposal_id),
+            };
+        }
+
+        #[test]
+        fn random_call_sequences_keep_invariants() {
+            for seed in 1..=20u64 {
+                ink::env::test::run_test::<Environment, _>(|accounts| {
+                    // Proposals start as drafts and queue behind two active ones.
+                    let mut governor = VotingContract::new(GovernorConfig {
+                        discussion_period: ONE_MINUTE,
+                        max_active_proposals: 2,
+                        ..test_config()
+                    });
+                    for voter in [accounts.bob, accounts.charlie, accounts.django] {
+                        ink::env::test::set_caller::<Environment>(voter);
+                        ink::env::test::set_value_transferred::<Environment>(100);
+                        governor.lock().unwrap();
+                    }
+                    ink::env::test::set_value_transferred::<Environment>(0);
+                    ink::env::test::set_account_balance::<Environment>(
+                        ink::env::test::callee::<Environment>(),
+                        TREASURY + governor.total_locked,
+                    );
+
+                    let mut rng = Rng(seed);
+                    let mut now = 1;
+                    let mut executed = Vec::new();
+                    let mut tallies: Vec<ProposalVote> = Vec::new();
+                    for _ in 0..80 {
+                        random_step(&mut governor, &mut rng, &mut now);
+
+                        let reserved = governor.total_escrowed
+                            + governor.total_locked
+                            + governor.total_bonded
+                            + governor.total_airdrop_reserved
+                            + governor.total_storage_deposits;
+                        let balance = ink::env::balance::<Environment>();
+                        assert!(reserved <= balance, "seed {}", seed);
+
+                        for proposal_id in 0..governor.next_proposal_id {
+                            let proposal = governor.proposals.get(proposal_id).unwrap();
+                            let status = governor.proposal_status.get(proposal_id).unwrap();
+                            if status.has(STATUS_EXECUTED) {
+                                let outcome = governor.outcomes.get(proposal_id).unwrap();
+                                assert!(outcome.passed, "seed {}", seed);
+                                if !executed.contains(&proposal_id) {
+                                    executed.push(proposal_id);
+                                    let escrowed = governor.total_escrowed;
+                                    assert!(governor.execute(proposal_id).is_err());
+                                    assert_eq!(governor.total_escrowed, escrowed);
+                                }
+                            }
+
+                            let votes = status.votes();
+                            if let Some(previous) = tallies.get(proposal_id as usize) {
+                                if now < proposal.vote_end {
+                                    assert!(votes.for_votes >= previous.for_votes);
+                                    assert!(votes.against_votes >= previous.against_votes);
+                                    assert!(votes.abstain_votes >= previous.abstain_votes);
+                                }
+                                tallies[proposal_id as usize] = votes;
+                            } else {
+                                tallies.push(votes);
+                            }
+                        }
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            }
+        }
+
+        #[ink::test]
+        fn builder_overrides_reach_the_proposal() {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            let mut governor = VotingContract::new(test_config());
+
+            ProposalBuilder::new()
+                .to(accounts.frank)
+                .title("Grant")
+                .category("GRANTS")
+                .amount(0)
+                .duration(60)
+                .action(ProposalAction::SetExtension(None))
+                .propose(&mut governor)
+                .unwrap();
+
+            let proposal = governor.get_proposal(0).unwrap();
+            assert_eq!(proposal.to, accounts.frank);
+            assert_eq!(proposal.amount, 0);
+            assert_eq!(proposal.vote_end - proposal.vote_start, 60 * ONE_MINUTE);
+            assert_eq!(governor.get_reference(0), Some("GRANTS-1".to_string()));
+            assert_eq!(governor.get_proposal_text(0).unwrap().title, "Grant");
+            assert_eq!(governor.get_proposal_actions(0).len(), 1);
+        }
+    }
+
+    /// Weight benchmarks of the core messages against a local contracts node,
+    /// printed as a table, failing if a message needs more than
+    /// `MAX_REF_TIME`. Run with
+    /// `cargo test --features e2e-tests core_message_weights -- --nocapture`.
+    /// The vote is ended by moving the governor's clock with `set_time_offset`,
+    /// which `e2e-tests` enables through `test-utils`. Alice deploys the
+    /// governor, so she holds the guardian role the message requires.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        const PROPOSAL_COUNTS: [u32; 3] = [1, 10, 25];
+        /// A tenth of the two seconds of computation in a block.
+        const MAX_REF_TIME: u64 = 200_000_000_000;
+
+        fn report(message: &str, scale: u32, ref_time: u64) {
+            println!("{:<24} {:>6} {:>16}", message, scale, ref_time);
+            assert!(
+                ref_time <= MAX_REF_TIME,
+                "{} at scale {} needs {} ref time, over the {} limit",
+                message,
+                scale,
+                ref_time,
+                MAX_REF_TIME
+            );
+        }
+
+        #[ink_e2e::test]
+        async fn core_message_weights(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let config = GovernorConfig {
+                weight_source: WeightSource::Native,
+                timelock: 0,
+                executor_grace_period: 0,
+                ..GovernorConfig::default_config(ink_e2e::account_id(
+                    ink_e2e::AccountKeyring::Alice,
+                ))
+            };
+            let constructor = VotingContractRef::new(config);
+            let governor = client
+                .instantiate("voting_contract", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let voters = [
+                ink_e2e::alice(),
+                ink_e2e::bob(),
+                ink_e2e::charlie(),
+                ink_e2e::dave(),
+                ink_e2e::eve(),
+                ink_e2e::ferdie(),
+            ];
+            for voter in voters.iter() {
+                let lock = build_message::<VotingContractRef>(governor.clone())
+                    .call(|governor| governor.lock());
+                client
+                    .call(voter, lock, 1_000_000, None)
+                    .await
+                    .expect("lock failed");
+            }
+
+            println!("{:<24} {:>6} {:>16}", "message", "scale", "ref time");
+            let mut proposals = 0;
+            for count in PROPOSAL_COUNTS {
+                while proposals < count {
+                    let mut request = ProposalBuilder::new()
+                        .to(ink_e2e::account_id(ink_e2e::AccountKeyring::Alice))
+                        .title(&format!("Benchmark {}", proposals))
+                        .amount(0)
+                        .action(ProposalAction::ChangeParameter(ParameterChange::Quorum(0)))
+                        .build();
+                    let propose =
+                        build_message::<VotingContractRef>(governor.clone()).call(|governor| {
+                            governor.propose(
+                                request.for_address,
+                                request.against_address,
+                                request.to,
+                                core::mem::take(&mut request.title),
+                                core::mem::take(&mut request.description),
+                                core::mem::take(&mut request.category),
+                                request.amount,
+                                request.duration,
+                                core::mem::take(&mut request.actions),
+                            )
+                        });
+                    let result = client
+                        .call(&ink_e2e::alice(), propose, 0, None)
+                        .await
+                        .expect("propose failed");
+                    proposals += 1;
+                    if proposals == count {
+                        let ref_time = result.dry_run.exec_result.gas_required.ref_time();
+                        report("propose", count, ref_time);
+                    }
+                }
+
+                let states = build_message::<VotingContractRef>(governor.clone())
+                    .call(|governor| governor.get_states(0, count));
+                let result = client
+                    .call_dry_run(&ink_e2e::alice(), &states, 0, None)
+                    .await;
+                report(
+                    "get_states",
+                    count,
+                    result.exec_result.gas_required.ref_time(),
+                );
+            }
+
+            let proposal_id = proposals - 1;
+            for (voted, voter) in voters.iter().enumerate() {
+                let vote = build_message::<VotingContractRef>(governor.clone())
+                    .call(|governor| governor.vote(proposal_id, VoteType::For));
+                let result = client
+                    .call(voter, vote, 0, None)
+                    .await
+                    .expect("vote failed");
+                let ref_time = result.dry_run.exec_result.gas_required.ref_time();
+                report("vote", voted as u32 + 1, ref_time);
+            }
+
+            let full = build_message::<VotingContractRef>(governor.clone())
+                .call(|governor| governor.get_proposal_full(proposal_id));
+            let result = client.call_dry_run(&ink_e2e::alice(), &full, 0, None).await;
+            let ref_time = result.exec_result.gas_required.ref_time();
+            report("get_proposal_full", voters.len() as u32, ref_time);
+
+            // Guardian powers never lapse under the default config, so Alice can
+            // still move the clock.
+            let skip_vote = build_message::<VotingContractRef>(governor.clone())
+                .call(|governor| governor.set_time_offset(ONE_MINUTE + 1_000));
+            client
+                .call(&ink_e2e::alice(), skip_vote, 0, None)
+                .await
+                .expect("set_time_offset failed");
+            let execute = build_message::<VotingContractRef>(governor.clone())
+                .call(|governor| governor.execute(proposal_id));
+            let result = client
+                .call(&ink_e2e::alice(), execute, 0, None)
+                .await
+                .expect("execute failed");
+            let ref_time = result.dry_run.exec_result.gas_required.ref_time();
+            report("execute", voters.len() as u32, ref_time);
+
+            Ok(())
         }
     }
 }
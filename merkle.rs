@@ -0,0 +1,60 @@
+//! Merkle proofs for claims against a root registered by a proposal.
+//!
+//! Leaves are the Blake2x256 hash of the SCALE encoded claim. Inner nodes hash
+//! the two children in ascending order, so proofs don't need to say on which
+//! side each sibling sits.
+
+use ink::env::hash::{Blake2x256, HashOutput};
+
+pub type Node = [u8; 32];
+
+fn hash(input: &[u8]) -> Node {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+    output
+}
+
+pub fn leaf<T: scale::Encode>(claim: &T) -> Node {
+    hash(&claim.encode())
+}
+
+fn parent(a: &Node, b: &Node) -> Node {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(low);
+    input[32..].copy_from_slice(high);
+    hash(&input)
+}
+
+/// Whether `proof` leads from `leaf` to `root`.
+pub fn verify(proof: &[Node], root: &Node, leaf: Node) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| parent(&node, sibling))
+        == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proofs_verify_against_their_root_only() {
+        let leaves = [
+            leaf(&(1u8, 10u128)),
+            leaf(&(2u8, 20u128)),
+            leaf(&(3u8, 30u128)),
+        ];
+        let left = parent(&leaves[0], &leaves[1]);
+        let root = parent(&left, &leaves[2]);
+
+        assert!(verify(&[leaves[1], leaves[2]], &root, leaves[0]));
+        assert!(verify(&[left], &root, leaves[2]));
+        assert!(!verify(
+            &[leaves[1], leaves[2]],
+            &root,
+            leaf(&(1u8, 11u128))
+        ));
+        assert!(!verify(&[], &root, leaves[0]));
+    }
+}
@@ -0,0 +1,17 @@
+//! Interface a successor governor implements to take over from this one.
+//!
+//! The outgoing governor calls `accept_migration` before moving its treasury,
+//! passing the governance token and the proposals still open, so the successor
+//! can link the token and re-create or reference them. Returning `false`
+//! aborts the hand-off.
+
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+
+#[ink::trait_definition]
+pub trait SuccessorGovernor {
+    #[ink(message)]
+    fn accept_migration(&mut self, governance_token: AccountId, open_proposals: Vec<u32>) -> bool;
+}
+
+pub const ACCEPT_MIGRATION: [u8; 4] = ink::selector_bytes!("SuccessorGovernor::accept_migration");
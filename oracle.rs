@@ -0,0 +1,40 @@
+//! Interface of the price feeds that convert USD-denominated payouts into the
+//! native token.
+//!
+//! The governor never interprets the fixed-point scale of a price: the USD
+//! amount of a proposal is written in the same units the feed reports, so the
+//! scale cancels out in the conversion. Only the number of decimals of the
+//! native token has to be known, and it is taken from the governor config.
+
+#[ink::trait_definition]
+pub trait PriceOracle {
+    /// USD value of one whole native token together with the timestamp, in
+    /// milliseconds, of its last update. `None` if the feed has no price yet.
+    #[ink(message)]
+    fn latest_price(&self) -> Option<(u128, u64)>;
+}
+
+pub const LATEST_PRICE: [u8; 4] = ink::selector_bytes!("PriceOracle::latest_price");
+
+/// Native amount worth `usd` at `price`, for a native token with `decimals`
+/// decimals, or `None` if it doesn't fit in a `u128`.
+pub fn native_amount(usd: u128, price: u128, decimals: u8) -> Option<u128> {
+    let unit = 10u128.checked_pow(decimals as u32)?;
+    usd.checked_mul(unit)?.checked_div(price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_amount_scales_by_decimals() {
+        // 1,000 USD at 5 USD per token, both with 6 decimals, on a 10-decimal chain.
+        assert_eq!(
+            native_amount(1_000_000_000, 5_000_000, 10),
+            Some(2_000_000_000_000)
+        );
+        assert_eq!(native_amount(1, 0, 10), None);
+        assert_eq!(native_amount(u128::MAX, 1, 18), None);
+    }
+}
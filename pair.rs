@@ -0,0 +1,18 @@
+//! Interface of the adapters that value DEX liquidity positions in the
+//! governance token.
+//!
+//! An LP token represents a share of both sides of a pair, and the governor
+//! can't know how any particular DEX stores its reserves. The adapter for a
+//! pair reports only the governance-token side of a position, so liquidity
+//! providers vote with the tokens they put in and never with the other asset.
+
+use ink::primitives::AccountId;
+
+#[ink::trait_definition]
+pub trait PairAdapter {
+    /// Amount of `token` that `liquidity` LP tokens of `lp_token` can redeem.
+    #[ink(message)]
+    fn underlying(&self, lp_token: AccountId, token: AccountId, liquidity: u128) -> u128;
+}
+
+pub const UNDERLYING: [u8; 4] = ink::selector_bytes!("PairAdapter::underlying");
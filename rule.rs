@@ -0,0 +1,97 @@
+//! Rules deciding whether a finalized tally passes, selectable per category.
+//!
+//! Each rule is a variant of `TallyRule` and decides both whether enough
+//! weight took part and whether the proposal won, so adding a rule only
+//! touches this module.
+
+use crate::tally;
+use crate::voting_contract::SUPER_MAJORITY_THRESHOLD;
+
+const SIMPLE_MAJORITY: u16 = 5_000;
+
+/// Final vote totals of a proposal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Votes {
+    pub for_votes: u128,
+    pub against_votes: u128,
+    pub abstain_votes: u128,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum TallyRule {
+    /// Turnout reaches the quorum and for votes exceed the proposal's approval
+    /// threshold of for and against votes.
+    #[default]
+    Threshold,
+    /// Turnout reaches the quorum and for votes outnumber against votes.
+    SimpleMajority,
+    /// Turnout reaches the quorum and for votes exceed
+    /// `SUPER_MAJORITY_THRESHOLD` of for and against votes.
+    SuperMajority,
+    /// For votes alone reach the quorum and outnumber against votes.
+    RelativeToQuorum,
+}
+
+impl TallyRule {
+    /// Weight the rule counts towards the quorum.
+    pub fn counted(self, votes: &Votes) -> u128 {
+        match self {
+            TallyRule::RelativeToQuorum => votes.for_votes,
+            _ => tally::turnout(votes.for_votes, votes.against_votes, votes.abstain_votes),
+        }
+    }
+
+    pub fn quorum_reached(self, votes: &Votes, quorum: u128) -> bool {
+        self.counted(votes) >= quorum
+    }
+
+    pub fn majority_reached(self, votes: &Votes, approval_threshold: u16) -> bool {
+        let threshold = match self {
+            TallyRule::Threshold => approval_threshold,
+            TallyRule::SuperMajority => SUPER_MAJORITY_THRESHOLD,
+            TallyRule::SimpleMajority | TallyRule::RelativeToQuorum => SIMPLE_MAJORITY,
+        };
+        tally::majority_reached(votes.for_votes, votes.against_votes, threshold)
+    }
+
+    pub fn passed(self, votes: &Votes, quorum: u128, approval_threshold: u16) -> bool {
+        match self {
+            TallyRule::Threshold => tally::passed(
+                votes.for_votes,
+                votes.against_votes,
+                votes.abstain_votes,
+                quorum,
+                approval_threshold,
+            ),
+            _ => {
+                self.quorum_reached(votes, quorum)
+                    && self.majority_reached(votes, approval_threshold)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_disagree_on_the_same_tally() {
+        let votes = Votes {
+            for_votes: 6,
+            against_votes: 4,
+            abstain_votes: 10,
+        };
+
+        assert!(TallyRule::Threshold.passed(&votes, 20, 5_000));
+        assert!(!TallyRule::Threshold.passed(&votes, 20, 6_000));
+        assert!(TallyRule::SimpleMajority.passed(&votes, 20, 9_000));
+        assert!(!TallyRule::SuperMajority.passed(&votes, 20, 0));
+        assert!(!TallyRule::RelativeToQuorum.passed(&votes, 20, 0));
+        assert!(TallyRule::RelativeToQuorum.passed(&votes, 6, 0));
+    }
+}
@@ -0,0 +1,76 @@
+//! Off-chain signatures authorizing governor operations submitted by relayers.
+//!
+//! A signed message is the Blake2x256 hash of the SCALE encoded domain
+//! separator, operation, payload, nonce and deadline. The domain separator
+//! binds it to one governor deployment and the operation tag to one kind of
+//! call, so a signature can't be replayed on another governor or passed off as
+//! a different operation. Signatures are ECDSA over secp256k1, and the signing
+//! account is the Blake2x256 hash of the compressed public key, as for
+//! Substrate's ECDSA accounts.
+
+use ink::env::hash::{Blake2x256, HashOutput};
+
+pub type Digest = [u8; 32];
+
+/// Bumped whenever the layout of signed messages changes, which invalidates
+/// signatures made for the old layout.
+const VERSION: u8 = 1;
+const DOMAIN: &[u8] = b"liminal-governor";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Operation {
+    Vote,
+    Propose,
+    Delegate,
+}
+
+fn hash<T: scale::Encode>(input: &T) -> Digest {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_encoded::<Blake2x256, _>(input, &mut output);
+    output
+}
+
+pub fn domain_separator(governor: &[u8; 32]) -> Digest {
+    hash(&(DOMAIN, VERSION, governor))
+}
+
+/// What the signer signs to authorize `operation` with `payload`.
+pub fn message<T: scale::Encode>(
+    domain: &Digest,
+    operation: Operation,
+    payload: &T,
+    nonce: u64,
+    deadline: u64,
+) -> Digest {
+    hash(&(domain, operation, payload, nonce, deadline))
+}
+
+/// Account whose key produced `signature` over `message`, if it's valid.
+pub fn signer(signature: &[u8; 65], message: &Digest) -> Option<[u8; 32]> {
+    let mut public_key = [0u8; 33];
+    ink::env::ecdsa_recover(signature, message, &mut public_key).ok()?;
+    let mut account = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+    Some(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_bound_to_governor_operation_and_nonce() {
+        let domain = domain_separator(&[1; 32]);
+        let signed = message(&domain, Operation::Vote, &7u32, 0, 100);
+
+        assert_eq!(signed, message(&domain, Operation::Vote, &7u32, 0, 100));
+        assert_ne!(
+            signed,
+            message(&domain_separator(&[2; 32]), Operation::Vote, &7u32, 0, 100)
+        );
+        assert_ne!(signed, message(&domain, Operation::Delegate, &7u32, 0, 100));
+        assert_ne!(signed, message(&domain, Operation::Vote, &7u32, 1, 100));
+        assert_ne!(signed, message(&domain, Operation::Vote, &7u32, 0, 101));
+    }
+}
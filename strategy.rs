@@ -0,0 +1,18 @@
+//! Interface of weight-strategy adapter contracts.
+//!
+//! Governance registers adapters, pinned to the code hash they were reviewed
+//! at, and can then count them as `WeightSource::Adapter` strategies. This
+//! lets the DAO change how voting power is computed without redeploying the
+//! governor. Adapters are called with the governor's hook gas limit; a call
+//! that fails or an adapter whose code changed counts as zero weight.
+
+use ink::primitives::AccountId;
+
+#[ink::trait_definition]
+pub trait WeightStrategy {
+    /// Weight of `account` as of `snapshot`, a timestamp in milliseconds.
+    #[ink(message)]
+    fn weight_at(&self, account: AccountId, snapshot: u64) -> u128;
+}
+
+pub const WEIGHT_AT: [u8; 4] = ink::selector_bytes!("WeightStrategy::weight_at");
@@ -0,0 +1,234 @@
+//! Vote counting and proposal state rules, free of any contract I/O so they
+//! can be unit-tested, fuzzed and reused off-chain.
+//!
+//! A proposal passes when its turnout, abstentions included, reaches its
+//! quorum and for votes exceed its approval threshold, in basis points, of
+//! the for and against votes.
+//!
+//! Ranked-choice proposals are counted by instant runoff. Ballots rank
+//! option indices from most to least preferred and are grouped by ranking,
+//! so a tally costs the same however many voters share a ranking.
+//! Each round counts every ballot for its highest-ranked option still in the
+//! race. An option with more than half of the counted weight wins; otherwise
+//! the weakest option is eliminated, ties going against the higher index.
+
+use crate::voting_contract::MAX_BASIS_POINTS;
+use ink::prelude::{vec, vec::Vec};
+
+/// Where a proposal stands in its lifecycle, derived from its status flags
+/// and timing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ProposalState {
+    /// In its discussion period, waiting for `activate`.
+    Draft,
+    /// Waiting for a free active proposal slot.
+    Queued,
+    Active,
+    /// Voting is over but the proposal hasn't been finalized yet.
+    Ended,
+    Defeated,
+    /// Passed and waiting for execution.
+    Succeeded,
+    /// Passed but not executed within the execution window.
+    Expired,
+    Executed,
+    Canceled,
+}
+
+/// Everything the state of a proposal depends on.
+#[derive(Debug, Clone, Default)]
+pub struct StateInputs {
+    pub canceled: bool,
+    pub executed: bool,
+    pub draft: bool,
+    pub queued: bool,
+    pub vote_end: u64,
+    /// Whether the proposal passed, once it has been finalized.
+    pub passed: Option<bool>,
+    /// End of the execution window, if passed proposals expire.
+    pub expires_at: Option<u64>,
+}
+
+pub fn state(inputs: &StateInputs, now: u64) -> ProposalState {
+    if inputs.canceled {
+        return ProposalState::Canceled;
+    }
+    if inputs.executed {
+        return ProposalState::Executed;
+    }
+    if inputs.draft {
+        return ProposalState::Draft;
+    }
+    if inputs.queued {
+        return ProposalState::Queued;
+    }
+    if now < inputs.vote_end {
+        return ProposalState::Active;
+    }
+    match inputs.passed {
+        None => ProposalState::Ended,
+        Some(false) => ProposalState::Defeated,
+        Some(true)
+            if inputs
+                .expires_at
+                .map_or(false, |expires_at| now >= expires_at) =>
+        {
+            ProposalState::Expired
+        }
+        Some(true) => ProposalState::Succeeded,
+    }
+}
+
+pub fn turnout(for_votes: u128, against_votes: u128, abstain_votes: u128) -> u128 {
+    for_votes
+        .saturating_add(against_votes)
+        .saturating_add(abstain_votes)
+}
+
+/// Whether the share of for votes among for and against votes exceeds
+/// `approval_threshold`.
+pub fn majority_reached(for_votes: u128, against_votes: u128, approval_threshold: u16) -> bool {
+    let cast = for_votes.saturating_add(against_votes);
+    for_votes.saturating_mul(MAX_BASIS_POINTS as u128)
+        > cast.saturating_mul(approval_threshold as u128)
+}
+
+pub fn passed(
+    for_votes: u128,
+    against_votes: u128,
+    abstain_votes: u128,
+    quorum: u128,
+    approval_threshold: u16,
+) -> bool {
+    turnout(for_votes, against_votes, abstain_votes) >= quorum
+        && majority_reached(for_votes, against_votes, approval_threshold)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Round {
+    /// Weight counted for each option, zero for options already eliminated.
+    pub counts: Vec<u128>,
+    /// Option eliminated at the end of the round, if no option won in it.
+    pub eliminated: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Runoff {
+    /// `None` if no ballot ranked any option.
+    pub winner: Option<u8>,
+    pub rounds: Vec<Round>,
+}
+
+pub fn instant_runoff(options: u8, ballots: &[(Vec<u8>, u128)]) -> Runoff {
+    let mut active = vec![true; options as usize];
+    let mut rounds = Vec::new();
+
+    loop {
+        let mut counts = vec![0u128; options as usize];
+        for (ranking, weight) in ballots {
+            let choice = ranking
+                .iter()
+                .find(|option| active.get(**option as usize) == Some(&true));
+            if let Some(option) = choice {
+                counts[*option as usize] = counts[*option as usize].saturating_add(*weight);
+            }
+        }
+
+        let total = counts
+            .iter()
+            .fold(0u128, |sum, count| sum.saturating_add(*count));
+        let remaining = active.iter().filter(|active| **active).count();
+        let leader = (0..options)
+            .filter(|option| active[*option as usize])
+            .max_by_key(|option| (counts[*option as usize], core::cmp::Reverse(*option)));
+        let winner = match leader {
+            None => None,
+            Some(_) if total == 0 => None,
+            Some(option) if remaining == 1 => Some(option),
+            Some(option) if counts[option as usize].saturating_mul(2) > total => Some(option),
+            Some(_) => {
+                let loser = (0..options)
+                    .filter(|option| active[*option as usize])
+                    .min_by_key(|option| (counts[*option as usize], core::cmp::Reverse(*option)));
+                if let Some(loser) = loser {
+                    active[loser as usize] = false;
+                }
+                rounds.push(Round {
+                    counts,
+                    eliminated: loser,
+                });
+                continue;
+            }
+        };
+
+        rounds.push(Round {
+            counts,
+            eliminated: None,
+        });
+        return Runoff { winner, rounds };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abstentions_count_towards_quorum_but_not_majority() {
+        assert!(passed(6, 4, 0, 10, 5_000));
+        assert!(!passed(5, 5, 10, 10, 5_000));
+        assert!(passed(1, 0, 9, 10, 5_000));
+        assert!(!passed(6, 3, 0, 10, 5_000));
+        assert!(!majority_reached(0, 0, 0));
+    }
+
+    #[test]
+    fn state_follows_flags_then_time() {
+        let mut inputs = StateInputs {
+            vote_end: 10,
+            ..Default::default()
+        };
+        assert_eq!(state(&inputs, 9), ProposalState::Active);
+        assert_eq!(state(&inputs, 10), ProposalState::Ended);
+
+        inputs.passed = Some(true);
+        inputs.expires_at = Some(20);
+        assert_eq!(state(&inputs, 19), ProposalState::Succeeded);
+        assert_eq!(state(&inputs, 20), ProposalState::Expired);
+
+        inputs.executed = true;
+        assert_eq!(state(&inputs, 20), ProposalState::Executed);
+        inputs.canceled = true;
+        assert_eq!(state(&inputs, 0), ProposalState::Canceled);
+    }
+
+    #[test]
+    fn eliminated_options_transfer_their_ballots() {
+        let ballots = [(vec![0, 1], 40), (vec![1, 0], 35), (vec![2, 1], 25)];
+        let runoff = instant_runoff(3, &ballots);
+
+        assert_eq!(runoff.winner, Some(1));
+        assert_eq!(runoff.rounds.len(), 2);
+        assert_eq!(runoff.rounds[0].counts, vec![40, 35, 25]);
+        assert_eq!(runoff.rounds[0].eliminated, Some(2));
+        assert_eq!(runoff.rounds[1].counts, vec![40, 60, 0]);
+    }
+
+    #[test]
+    fn majority_in_first_round_wins_and_empty_tally_has_no_winner() {
+        let runoff = instant_runoff(2, &[(vec![1], 10), (vec![0], 5)]);
+        assert_eq!(runoff.winner, Some(1));
+        assert_eq!(runoff.rounds.len(), 1);
+
+        assert_eq!(instant_runoff(2, &[]).winner, None);
+    }
+}
@@ -0,0 +1,53 @@
+//! Minimal handle for PSP22 contracts the governor instantiates itself, so
+//! `build_create` can hand back the new account without a generated
+//! contract reference, and helpers for the `PSP22Metadata` extension.
+
+use ink::env::call::FromAccountId;
+use ink::env::{ContractEnv, DefaultEnvironment};
+use ink::primitives::AccountId;
+
+pub struct TokenRef(AccountId);
+
+impl TokenRef {
+    pub fn account_id(&self) -> AccountId {
+        self.0
+    }
+}
+
+impl ContractEnv for TokenRef {
+    type Env = DefaultEnvironment;
+}
+
+impl FromAccountId<DefaultEnvironment> for TokenRef {
+    fn from_account_id(account_id: AccountId) -> Self {
+        Self(account_id)
+    }
+}
+
+pub const BALANCE_OF: [u8; 4] = ink::selector_bytes!("PSP22::balance_of");
+pub const TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+pub const APPROVE: [u8; 4] = ink::selector_bytes!("PSP22::approve");
+pub const TOKEN_NAME: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_name");
+pub const TOKEN_SYMBOL: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_symbol");
+pub const TOKEN_DECIMALS: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_decimals");
+
+/// `amount` whole tokens in the token's smallest unit, or `None` if that
+/// doesn't fit in a `u128`.
+pub fn to_smallest_unit(amount: u128, decimals: u8) -> Option<u128> {
+    10u128
+        .checked_pow(decimals as u32)
+        .and_then(|scale| amount.checked_mul(scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_tokens_scale_by_decimals() {
+        assert_eq!(to_smallest_unit(5, 0), Some(5));
+        assert_eq!(to_smallest_unit(5, 12), Some(5_000_000_000_000));
+        assert_eq!(to_smallest_unit(1, 39), None);
+        assert_eq!(to_smallest_unit(u128::MAX, 1), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! Snapshot bookkeeping for the voting weight the governor tracks itself.
+//!
+//! Delegated token weight and locked native balance are recorded as
+//! checkpoints. A proposal's snapshot is its `vote_start`, and a vote only
+//! counts weight recorded strictly before it: tokens acquired, delegated or
+//! locked in the snapshot block or later can never add weight to that
+//! proposal, so nobody can promise weight they didn't hold when voting opened.
+
+use ink::prelude::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Checkpoint {
+    pub timestamp: u64,
+    pub weight: u128,
+}
+
+/// Weight in effect just before `snapshot`. Checkpoints are sorted by
+/// timestamp, so this is a binary search.
+pub fn weight_before(checkpoints: &[Checkpoint], snapshot: u64) -> u128 {
+    match checkpoints.partition_point(|checkpoint| checkpoint.timestamp < snapshot) {
+        0 => 0,
+        index => checkpoints[index - 1].weight,
+    }
+}
+
+/// Weight held throughout the `holding` period ending just before `snapshot`:
+/// the lowest weight in effect at any point of it. Weight acquired within the
+/// period doesn't count, weight given up within it is lost.
+pub fn held_before(checkpoints: &[Checkpoint], snapshot: u64, holding: u64) -> u128 {
+    let start = snapshot.saturating_sub(holding);
+    let held = weight_before(checkpoints, start.saturating_add(1).min(snapshot));
+    checkpoints
+        .iter()
+        .filter(|checkpoint| checkpoint.timestamp > start && checkpoint.timestamp < snapshot)
+        .fold(held, |held, checkpoint| held.min(checkpoint.weight))
+}
+
+pub fn latest(checkpoints: &[Checkpoint]) -> u128 {
+    checkpoints.last().map_or(0, |checkpoint| checkpoint.weight)
+}
+
+/// Records `weight` as of `timestamp`. Checkpoints written in the same block
+/// overwrite each other.
+pub fn push(checkpoints: &mut Vec<Checkpoint>, timestamp: u64, weight: u128) {
+    match checkpoints.last_mut() {
+        Some(last) if last.timestamp == timestamp => last.weight = weight,
+        _ => checkpoints.push(Checkpoint { timestamp, weight }),
+    }
+}
+
+/// Drops checkpoints no snapshot at or after `cutoff` can need: all older
+/// than `cutoff` except the latest of them, which still holds at `cutoff`.
+pub fn prune(checkpoints: &mut Vec<Checkpoint>, cutoff: u64) {
+    let older = checkpoints.partition_point(|checkpoint| checkpoint.timestamp < cutoff);
+    if older > 1 {
+        checkpoints.drain(..older - 1);
+    }
+}
+
+/// `weight` after halving once for every full `period` in `elapsed`. A zero
+/// `period` disables decay.
+pub fn decayed(weight: u128, elapsed: u64, period: u64) -> u128 {
+    if period == 0 {
+        return weight;
+    }
+    match elapsed / period {
+        halvings if halvings >= 128 => 0,
+        halvings => weight >> halvings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weight_recorded_at_or_after_snapshot_does_not_count() {
+        let mut checkpoints = Vec::new();
+        push(&mut checkpoints, 10, 100);
+        push(&mut checkpoints, 20, 500);
+
+        assert_eq!(weight_before(&checkpoints, 10), 0);
+        assert_eq!(weight_before(&checkpoints, 20), 100);
+        assert_eq!(weight_before(&checkpoints, 21), 500);
+        assert_eq!(latest(&checkpoints), 500);
+    }
+
+    #[test]
+    fn only_weight_held_for_the_whole_period_counts() {
+        let mut checkpoints = Vec::new();
+        push(&mut checkpoints, 10, 100);
+        push(&mut checkpoints, 20, 500);
+        push(&mut checkpoints, 25, 300);
+
+        assert_eq!(held_before(&checkpoints, 30, 0), 300);
+        assert_eq!(held_before(&checkpoints, 30, 5), 300);
+        assert_eq!(held_before(&checkpoints, 30, 15), 100);
+        assert_eq!(held_before(&checkpoints, 30, 25), 0);
+        assert_eq!(held_before(&checkpoints, 20, 10), 100);
+    }
+
+    #[test]
+    fn same_block_checkpoints_are_merged() {
+        let mut checkpoints = Vec::new();
+        push(&mut checkpoints, 10, 100);
+        push(&mut checkpoints, 10, 0);
+
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(weight_before(&checkpoints, 11), 0);
+    }
+
+    #[test]
+    fn pruning_keeps_the_weight_at_the_cutoff() {
+        let mut checkpoints = Vec::new();
+        push(&mut checkpoints, 10, 100);
+        push(&mut checkpoints, 20, 200);
+        push(&mut checkpoints, 30, 300);
+        push(&mut checkpoints, 40, 400);
+
+        prune(&mut checkpoints, 35);
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(weight_before(&checkpoints, 36), 300);
+        assert_eq!(weight_before(&checkpoints, 41), 400);
+
+        prune(&mut checkpoints, 5);
+        assert_eq!(checkpoints.len(), 2);
+    }
+
+    #[test]
+    fn weight_halves_every_full_period() {
+        assert_eq!(decayed(100, 9, 10), 100);
+        assert_eq!(decayed(100, 10, 10), 50);
+        assert_eq!(decayed(100, 25, 10), 25);
+        assert_eq!(decayed(100, u64::MAX, 1), 0);
+        assert_eq!(decayed(100, 1_000, 0), 100);
+    }
+}